@@ -0,0 +1,126 @@
+use std::{collections::HashMap, ops::RangeBounds};
+
+use crate::{ConsumeEffect, Item};
+
+/// An index of the items in the game for looking items up and filtering them.
+#[derive(Debug, Default)]
+pub struct ItemCatalog {
+    items: Vec<Item>,
+    /// Lowercase item name to the item's index.
+    by_name: HashMap<String, usize>
+}
+
+impl ItemCatalog {
+    pub fn new(items: Vec<Item>) -> Self {
+        let by_name = items.iter()
+            .enumerate()
+            .map(|(index, item)| (item.name.to_lowercase(), index))
+            .collect();
+        Self { items, by_name }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter()
+    }
+
+    /// Find an item by its name ignoring case.
+    pub fn get(&self, name: &str) -> Option<&Item> {
+        self.by_name.get(&name.to_lowercase()).map(|index| &self.items[*index])
+    }
+
+    /// Start filtering the items, every filter added to the query has to match.
+    pub fn query(&self) -> ItemQuery<'_> {
+        ItemQuery { catalog: self, filters: Vec::new() }
+    }
+
+    /// Find the items whose quality descriptions contain every word of the text.
+    pub fn search(&self, text: &str) -> Vec<&Item> {
+        self.query().description(text).collect()
+    }
+}
+
+impl From<Vec<Item>> for ItemCatalog {
+    fn from(items: Vec<Item>) -> Self {
+        Self::new(items)
+    }
+}
+
+type ItemFilter<'a> = Box<dyn Fn(&Item) -> bool + 'a>;
+
+/// Filters over the items of a catalog, see [`ItemCatalog::query`].
+pub struct ItemQuery<'a> {
+    catalog: &'a ItemCatalog,
+    filters: Vec<ItemFilter<'a>>
+}
+
+impl<'a> ItemQuery<'a> {
+    /// Only items whose level is in the range.
+    pub fn level<R: RangeBounds<usize> + 'a>(self, range: R) -> Self {
+        self.filter(move |item| range.contains(&item.level))
+    }
+
+    /// Only items of a type.
+    pub fn item_type(self, item_type: usize) -> Self {
+        self.filter(move |item| item.item_type == item_type)
+    }
+
+    /// Only items whose worth multiplier (their sell price at the lowest quality) is in the range.
+    pub fn worth<R: RangeBounds<usize> + 'a>(self, range: R) -> Self {
+        self.filter(move |item| range.contains(&item.worth_multiplier))
+    }
+
+    /// Only items that can be consumed.
+    pub fn consumable(self) -> Self {
+        self.filter(|item| item.consume_effects.as_ref().is_some_and(|effects| !effects.is_empty()))
+    }
+
+    /// Only items that have a consume effect matching the predicate, e.g. `|effect| matches!(effect, ConsumeEffect::AddCredits(credits) if credits.min > 100)`.
+    /// Use [`ItemQuery::consume_effect_kind`] to filter by the kind of effect.
+    pub fn consume_effect<F: Fn(&ConsumeEffect) -> bool + 'a>(self, predicate: F) -> Self {
+        self.filter(move |item| item.consume_effects.iter().flatten().any(&predicate))
+    }
+
+    /// Only items that have a consume effect of a kind, named after its variant ignoring case, e.g. `"RestoreEnergy"`.
+    /// No item matches a kind that isn't one of [`ConsumeEffect::VARIANTS`].
+    pub fn consume_effect_kind(self, kind: &str) -> Self {
+        let tag = ConsumeEffect::VARIANTS.iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(kind))
+            .map(|(tag, _)| *tag);
+        self.filter(move |item| item.consume_effects.iter().flatten().any(|effect| Some(effect.tag()) == tag))
+    }
+
+    /// Only items whose quality descriptions contain every word of the text, ignoring case.
+    pub fn description(self, text: &str) -> Self {
+        let words: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+        self.filter(move |item| {
+            let descriptions = item.quality_descriptions.join(" ").to_lowercase();
+            words.iter().all(|word| descriptions.contains(word.as_str()))
+        })
+    }
+
+    /// Only items matching a custom predicate.
+    pub fn filter<F: Fn(&Item) -> bool + 'a>(mut self, predicate: F) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a Item> + '_ {
+        self.catalog.items.iter().filter(|item| self.filters.iter().all(|filter| filter(item)))
+    }
+
+    pub fn collect(self) -> Vec<&'a Item> {
+        self.iter().collect()
+    }
+
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+}
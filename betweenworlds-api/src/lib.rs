@@ -1,9 +1,16 @@
-use std::{fmt::{Write, self}, collections::HashMap};
+use std::{fmt::{Write, self}, collections::{HashMap, hash_map::DefaultHasher}, hash::{Hash, Hasher}, time::SystemTime};
 
 use bitflags::bitflags;
-use reqwest::{Url, blocking::Client as ReqwestClient};
-use serde::{Deserialize, de::DeserializeOwned};
-use serde_json::Value;
+use reqwest::{Url, StatusCode, blocking::Client as ReqwestClient};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+mod catalog;
+mod players;
+mod snapshot;
+pub use catalog::{ItemCatalog, ItemQuery};
+pub use players::PlayerDirectory;
+pub use snapshot::{LeaderboardSnapshot, SnapshotEntry, SnapshotDiff, CategoryDiff, EntryChange};
 
 const BASE_URL: &str = "https://api.betweenworlds.net/v1";
 
@@ -40,6 +47,32 @@ impl Client {
         self.get(url)
     }
 
+    /// Get a directory of every player on the leaderboards, used to search players by name.
+    pub fn get_player_directory(&self) -> Result<PlayerDirectory, ApiError> {
+        Ok(PlayerDirectory::from_snapshot(&self.get_leaderboard_snapshot()?))
+    }
+
+    /// Find a player by their name ignoring case and return the name as it's spelled in the game.
+    /// Looks in the directory first and falls back to asking the api for the exact name.
+    /// Returns `None` if the player doesn't exist.
+    pub fn find_player(&self, name: &str, directory: &PlayerDirectory) -> Result<Option<String>, ApiError> {
+        if let Some(name) = directory.lookup(name) {
+            return Ok(Some(name.to_string()));
+        }
+        match self.get_user(name, UserDataFlags::empty()) {
+            Ok(user) => Ok(Some(user.name)),
+            Err(ApiError::NotFound) => Ok(None),
+            Err(error) => Err(error)
+        }
+    }
+
+    /// Check that the client's credentials are accepted by the api.
+    /// Performs a cheap authenticated request (the auth id's own profile without any extra data).
+    /// Errors that say nothing about the credentials themselves are returned as is.
+    pub fn verify_credentials(&self) -> Result<CredentialsStatus, ApiError> {
+        CredentialsStatus::of(self.get_user(&self.auth_id, UserDataFlags::empty()))
+    }
+
     /// Get all the items in the game
     pub fn get_items(&self) -> Result<Vec<Item>, ApiError> {
         let url = Url::parse_with_params(
@@ -58,31 +91,49 @@ impl Client {
         Ok(map)
     }
 
+    /// Get all the items in the game indexed for searching
+    pub fn get_item_catalog(&self) -> Result<ItemCatalog, ApiError> {
+        Ok(ItemCatalog::new(self.get_items()?))
+    }
+
     /// Get the leaderboards data
     pub fn get_leaderboards(&self, data_flags: LeaderboardsFlags) -> Result<Leaderboards, ApiError> {
-        let mut url = Url::parse(&format!("{BASE_URL}/leaderboards")).expect("Unable to parse base url.");
-        let query = format!(
-            "authId={}&apiKey={}&{}", 
-            self.auth_id, 
-            self.api_key, 
-            self.leaderboard_flags_to_query(data_flags)
-        );
-        url.set_query(Some(&query));
-        self.get(url)
+        self.get(self.leaderboards_url(None, data_flags))
+    }
+
+    /// Get the leaderboards data only if it changed since the last time the detector saw it.
+    /// The data comes with the detector to keep once the caller handled it, see [`ChangeDetector`].
+    pub fn get_leaderboards_if_changed(&self, data_flags: LeaderboardsFlags, detector: &ChangeDetector) -> Result<Conditional<(Leaderboards, ChangeDetector)>, ApiError> {
+        self.get_if_changed(self.leaderboards_url(None, data_flags), detector)
     }
 
     /// get a specific user from the leaderboards
     pub fn get_leaderboard_user(&self, username: &str, data_flags: LeaderboardsFlags) -> Result<LeaderboardUser, ApiError> {
+        let leaderboards = self.get(self.leaderboards_url(Some(username), data_flags))?;
+        Self::leaderboard_user_from(leaderboards)
+    }
+
+    /// get a specific user from the leaderboards only if their entries changed since the last time the detector saw them.
+    /// The user comes with the detector to keep once the caller handled them, see [`ChangeDetector`].
+    pub fn get_leaderboard_user_if_changed(&self, username: &str, data_flags: LeaderboardsFlags, detector: &ChangeDetector) -> Result<Conditional<(LeaderboardUser, ChangeDetector)>, ApiError> {
+        match self.get_if_changed(self.leaderboards_url(Some(username), data_flags), detector)? {
+            Conditional::Modified((leaderboards, next)) => Ok(Conditional::Modified((Self::leaderboard_user_from(leaderboards)?, next))),
+            Conditional::NotModified => Ok(Conditional::NotModified)
+        }
+    }
+
+    fn leaderboards_url(&self, username: Option<&str>, data_flags: LeaderboardsFlags) -> Url {
         let mut url = Url::parse(&format!("{BASE_URL}/leaderboards")).expect("Unable to parse base url.");
-        let query = format!(
-            "authId={}&apiKey={}&name={}&{}", 
-            self.auth_id, 
-            self.api_key, 
-            username,
-            self.leaderboard_flags_to_query(data_flags)
-        );
+        let mut query = format!("authId={}&apiKey={}", self.auth_id, self.api_key);
+        if let Some(username) = username {
+            write!(&mut query, "&name={}", username).expect("Couldnt write to string.");
+        }
+        write!(&mut query, "&{}", self.leaderboard_flags_to_query(data_flags)).expect("Couldnt write to string.");
         url.set_query(Some(&query));
-        let leaderboards: Leaderboards = self.get(url)?;
+        url
+    }
+
+    fn leaderboard_user_from(leaderboards: Leaderboards) -> Result<LeaderboardUser, ApiError> {
         let mut user = LeaderboardUser::default();
         // TODO: Refactor that
         if let Some(mut credits) = leaderboards.credits {
@@ -154,38 +205,27 @@ impl Client {
 
     fn leaderboard_flags_to_query(&self, flags: LeaderboardsFlags) -> String {
         let mut query = DelimiterStringWriter::new("&");
-        // TODO: Refactor that
-        if flags.has(LeaderboardsFlags::Credits) {
-            query.add("credits").expect("Couldnt write to string.");
-        }
-        if flags.has(LeaderboardsFlags::HighestLevels) {
-            query.add("highestLevels").expect("Couldnt write to string.");
-        }
-        if flags.has(LeaderboardsFlags::CombatsWon) {
-            query.add("combatsWon").expect("Couldnt write to string.");
-        }
-        if flags.has(LeaderboardsFlags::ItemsCrafted) {
-            query.add("itemsCrafted").expect("Couldnt write to string.");
-        }
-        if flags.has(LeaderboardsFlags::JobsPerformed) {
-            query.add("jobsPerformed").expect("Couldnt write to string.");
-        }
-        if flags.has(LeaderboardsFlags::Overdoses) {
-            query.add("overdoses").expect("Couldnt write to string.");
-        }
-        if flags.has(LeaderboardsFlags::MissionsCompleted) {
-            query.add("missionsCompleted").expect("Couldnt write to string.");
+        for category in LeaderboardCategory::ALL {
+            if flags.has(category.flag()) {
+                query.add(category.query_name()).expect("Couldnt write to string.");
+            }
         }
         query.get()
     }
 
+    /// Get all the leaderboards at once as a snapshot.
+    pub fn get_leaderboard_snapshot(&self) -> Result<LeaderboardSnapshot, ApiError> {
+        let leaderboards = self.get_leaderboards(LeaderboardsFlags::all())?;
+        Ok(LeaderboardSnapshot::from_leaderboards(leaderboards, SystemTime::now()))
+    }
+
     fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T, ApiError> {
         match self.reqwest_client.get(url).send() {
             Ok(response) => {
                 match response.error_for_status() {
                     Ok(response) => {
-                        let text = response.text().or_else(|_| {Err(ApiError::Other)})?;
-                        let user = serde_json::from_str::<T>(&text).or_else(|error| {Err(ApiError::Deserialization(error))})?;
+                        let text = response.text().map_err(|_| ApiError::Other)?;
+                        let user = parse::<T>(&text)?;
                         Ok(user)
                     },
                     Err(error) => Err(self.get_error(error))
@@ -196,21 +236,97 @@ impl Client {
         }
     }
 
-    fn get_error(&self, error: reqwest::Error) -> ApiError {
-        if error.is_timeout() {
-            ApiError::RequestTimeout
+    /// Get data if it changed along with the detector to keep once the caller handled the data,
+    /// the detector itself is left as is so a caller that fails sees the data again.
+    fn get_if_changed<T: DeserializeOwned>(&self, url: Url, detector: &ChangeDetector) -> Result<Conditional<(T, ChangeDetector)>, ApiError> {
+        let mut request = self.reqwest_client.get(url);
+        if let Some(etag) = detector.etag() {
+            request = request.header(IF_NONE_MATCH, etag);
         }
-        else if let Some(status) = error.status() {
-            match status.as_u16() {
-                401 => ApiError::Unauthorized,
-                404 => ApiError::NotFound,
-                _ => ApiError::Other
-            }
+        if let Some(last_modified) = detector.last_modified() {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
         }
-        else {
-            ApiError::Other
+
+        let response = request.send().map_err(|error| self.get_error(error))?;
+        let response = response.error_for_status().map_err(|error| self.get_error(error))?;
+        let status = response.status();
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let text = response.text().map_err(|_| ApiError::Other)?;
+
+        match detector.observe(status.as_u16(), etag, last_modified, &text) {
+            Observation::Changed(next) => Ok(Conditional::Modified((parse::<T>(&text)?, next))),
+            Observation::Unchanged => Ok(Conditional::NotModified)
         }
     }
+
+    fn get_error(&self, error: reqwest::Error) -> ApiError {
+        ApiError::of_failure(error.is_timeout(), error.is_connect(), error.status().map(|status| status.as_u16()))
+    }
+}
+
+/// Remembers the last response of a polled request, so a caller can cheaply skip responses that didn't change.
+/// The server's `ETag` and `Last-Modified` validators are sent back as a conditional request,
+/// and when the server doesn't honour them the response body is compared by hash instead.
+/// Use a separate detector for every request you poll.
+/// The requests return changed data with the next detector, keep it only once the data was handled
+/// so a caller that fails sees the data again on the next poll.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangeDetector {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_hash: Option<u64>
+}
+
+impl ChangeDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `ETag` of the last response, sent as `If-None-Match`.
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// The `Last-Modified` date of the last response, sent as `If-Modified-Since`.
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+
+    /// Decide whether a response changed from its status, validators and body.
+    /// A `304 Not Modified` or a body with the same hash as the last one is unchanged.
+    /// Otherwise the detector to keep for the next request is returned, the caller replaces this one with it
+    /// once it handled the response.
+    pub fn observe(&self, status: u16, etag: Option<String>, last_modified: Option<String>, body: &str) -> Observation {
+        if status == StatusCode::NOT_MODIFIED.as_u16() {
+            return Observation::Unchanged;
+        }
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        let content_hash = hasher.finish();
+        if self.content_hash == Some(content_hash) {
+            return Observation::Unchanged;
+        }
+        Observation::Changed(ChangeDetector { etag, last_modified, content_hash: Some(content_hash) })
+    }
+}
+
+/// What a [`ChangeDetector`] makes of a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Observation {
+    Unchanged,
+    /// The response changed, this is the detector to keep.
+    Changed(ChangeDetector)
+}
+
+/// The result of a request made with a [`ChangeDetector`].
+#[derive(Debug)]
+pub enum Conditional<T> {
+    /// The data changed (or was never seen before).
+    Modified(T),
+    /// The data is the same as the last time.
+    NotModified
 }
 
 bitflags! {
@@ -224,7 +340,7 @@ bitflags! {
 
 impl UserDataFlags {
     pub fn has(&self, flags: Self) -> bool {
-        0 < (flags & *self).bits() as u32
+        0 < (flags & *self).bits()
     }
 }
 
@@ -243,19 +359,155 @@ bitflags! {
 
 impl LeaderboardsFlags {
     pub fn has(&self, flags: Self) -> bool {
-        0 < (flags & *self).bits() as u32
+        0 < (flags & *self).bits()
     }
 }
 
+/// One of the leaderboards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LeaderboardCategory {
+    Credits,
+    HighestLevels,
+    CombatsWon,
+    ItemsCrafted,
+    JobsPerformed,
+    Overdoses,
+    MissionsCompleted
+}
+
+impl LeaderboardCategory {
+    pub const ALL: [LeaderboardCategory; 7] = [
+        LeaderboardCategory::Credits,
+        LeaderboardCategory::HighestLevels,
+        LeaderboardCategory::CombatsWon,
+        LeaderboardCategory::ItemsCrafted,
+        LeaderboardCategory::JobsPerformed,
+        LeaderboardCategory::Overdoses,
+        LeaderboardCategory::MissionsCompleted,
+    ];
+
+    /// The flag used to request this leaderboard.
+    pub fn flag(&self) -> LeaderboardsFlags {
+        match self {
+            LeaderboardCategory::Credits => LeaderboardsFlags::Credits,
+            LeaderboardCategory::HighestLevels => LeaderboardsFlags::HighestLevels,
+            LeaderboardCategory::CombatsWon => LeaderboardsFlags::CombatsWon,
+            LeaderboardCategory::ItemsCrafted => LeaderboardsFlags::ItemsCrafted,
+            LeaderboardCategory::JobsPerformed => LeaderboardsFlags::JobsPerformed,
+            LeaderboardCategory::Overdoses => LeaderboardsFlags::Overdoses,
+            LeaderboardCategory::MissionsCompleted => LeaderboardsFlags::MissionsCompleted,
+        }
+    }
+
+    /// The name the api uses for this leaderboard.
+    pub fn query_name(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Credits => "credits",
+            LeaderboardCategory::HighestLevels => "highestLevels",
+            LeaderboardCategory::CombatsWon => "combatsWon",
+            LeaderboardCategory::ItemsCrafted => "itemsCrafted",
+            LeaderboardCategory::JobsPerformed => "jobsPerformed",
+            LeaderboardCategory::Overdoses => "overdoses",
+            LeaderboardCategory::MissionsCompleted => "missionsCompleted",
+        }
+    }
+
+    /// A human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Credits => "credits",
+            LeaderboardCategory::HighestLevels => "level",
+            LeaderboardCategory::CombatsWon => "combats won",
+            LeaderboardCategory::ItemsCrafted => "items crafted",
+            LeaderboardCategory::JobsPerformed => "jobs performed",
+            LeaderboardCategory::Overdoses => "overdoses",
+            LeaderboardCategory::MissionsCompleted => "missions completed",
+        }
+    }
+}
+
+/// Deserializes a response keeping track of where the deserialization failed.
+/// Like `serde_json::from_str` anything but whitespace after the value is an error.
+fn parse<T: DeserializeOwned>(text: &str) -> Result<T, ApiError> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(ApiError::Deserialization)?;
+    // The trailing characters aren't inside the value so the error has an empty path.
+    deserializer.end()
+        .map_err(|error| ApiError::Deserialization(serde_path_to_error::Error::new(serde_path_to_error::Track::new().path(), error)))?;
+    Ok(value)
+}
+
 #[derive(Debug)]
 pub enum ApiError {
     NotFound,
     RequestTimeout,
     Unauthorized,
-    Deserialization(serde_json::Error),
+    /// The api server could not be reached at all.
+    Unreachable,
+    /// The response didn't match the expected format, the error tells where, e.g. `[12].consumeEffects[0]`.
+    Deserialization(serde_path_to_error::Error<serde_json::Error>),
     Other
 }
 
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound => write!(f, "not found"),
+            ApiError::RequestTimeout => write!(f, "the request timed out"),
+            ApiError::Unauthorized => write!(f, "invalid credentials"),
+            ApiError::Unreachable => write!(f, "unable to reach the api"),
+            ApiError::Deserialization(error) => write!(f, "unable to parse the response: {error}"),
+            ApiError::Other => write!(f, "unknown error"),
+        }
+    }
+}
+
+impl ApiError {
+    /// The error of a failed request: whether it timed out, whether the connection failed
+    /// and the status of the response if there was one.
+    pub fn of_failure(timed_out: bool, connect_failed: bool, status: Option<u16>) -> Self {
+        if timed_out {
+            ApiError::RequestTimeout
+        }
+        else if connect_failed {
+            ApiError::Unreachable
+        }
+        else {
+            match status {
+                Some(401 | 403) => ApiError::Unauthorized,
+                Some(404) => ApiError::NotFound,
+                _ => ApiError::Other
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// The result of [`Client::verify_credentials`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialsStatus {
+    /// The api accepted the auth id and api key.
+    Valid,
+    /// The api rejected the auth id or the api key.
+    Unauthorized,
+    /// The api couldn't be reached, so the credentials weren't checked.
+    Unreachable
+}
+
+impl CredentialsStatus {
+    /// The status told by the result of an authenticated request,
+    /// errors that say nothing about the credentials themselves are returned as is.
+    pub fn of<T>(result: Result<T, ApiError>) -> Result<Self, ApiError> {
+        match result {
+            Ok(_) => Ok(CredentialsStatus::Valid),
+            Err(ApiError::Unauthorized) => Ok(CredentialsStatus::Unauthorized),
+            Err(ApiError::Unreachable | ApiError::RequestTimeout) => Ok(CredentialsStatus::Unreachable),
+            Err(error) => Err(error)
+        }
+    }
+}
+
 
 #[derive(Debug, Deserialize)]
 pub struct User {
@@ -308,49 +560,56 @@ pub struct Item {
 
 use serde_enums::SerdeEnum;
 
+/// An effect of consuming an item, the api tags every effect with its discriminant.
 #[derive(Debug, SerdeEnum)]
 #[repr(u8)]
 pub enum ConsumeEffect {
     Unknown = 0,
-    RestoreHealth(MinMax),
-    RestoreEnergy(MinMax),
-    RestoreSpirit(MinMax),
-    BuffHealth(MinMax),
-    BuffEnergy(MinMax),
-    BuffSpirit(MinMax),
-    DecreaseHealth(MinMax),
-    DecreaseEnergy(MinMax),
-    DecreaseSpirit(MinMax),
-    DebuffHealth(MinMax),
-    DebuffEnergy(MinMax),
-    DebuffSpirit(MinMax),
-    AddCredits(AddCreditsEffect),
-    RemoveCredits(MinMax),
-    AcceptMission(AcceptMissionEffect),
-    AddItem(AddItemEffect),
+    RestoreHealth(MinMax) = 1,
+    RestoreEnergy(MinMax) = 2,
+    RestoreSpirit(MinMax) = 3,
+    BuffHealth(MinMax) = 4,
+    BuffEnergy(MinMax) = 5,
+    BuffSpirit(MinMax) = 6,
+    DecreaseHealth(MinMax) = 7,
+    DecreaseEnergy(MinMax) = 8,
+    DecreaseSpirit(MinMax) = 9,
+    DebuffHealth(MinMax) = 10,
+    DebuffEnergy(MinMax) = 11,
+    DebuffSpirit(MinMax) = 12,
+    AddCredits(AddCreditsEffect) = 13,
+    RemoveCredits(MinMax) = 14,
+    AcceptMission(AcceptMissionEffect) = 15,
+    AddItem(AddItemEffect) = 16,
+    /// An effect this library doesn't know about yet.
+    #[serde_enum(other)]
+    Unrecognized {
+        tag: u64,
+        raw: serde_json::Value
+    },
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MinMax {
     pub min: isize,
     pub max: isize
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AddCreditsEffect {
     pub min: isize,
     pub max: isize,
-    #[serde(rename="worthMultiplier")]
+    #[serde(rename="worthMultiplier", skip_serializing_if = "Option::is_none")]
     pub worth_multiplier: Option<usize>
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AcceptMissionEffect {
     #[serde(rename="missionName")]
     pub mission_name: String
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AddItemEffect {
     pub chance: f32,
     #[serde(rename="itemName")]
@@ -472,7 +731,7 @@ impl<'a> DelimiterStringWriter<'a> {
     }
 
     pub fn add(&mut self, element: &str) -> fmt::Result {
-        if self.string.len() > 0 {
+        if !self.string.is_empty() {
             write!(&mut self.string, "{}{}", self.delimiter, element)?;
         }
         else {
@@ -0,0 +1,94 @@
+use crate::LeaderboardSnapshot;
+
+/// A searchable list of player names.
+/// The api has no way to list players, so the directory is usually built from the leaderboards
+/// and only knows the players that appear on at least one of them.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerDirectory {
+    /// (lowercase name, name) pairs sorted by the lowercase name then the name,
+    /// names only differing by case are different players.
+    names: Vec<(String, String)>
+}
+
+impl PlayerDirectory {
+    pub fn new<I: IntoIterator<Item = String>>(names: I) -> Self {
+        let mut directory = Self::default();
+        for name in names {
+            directory.insert(name);
+        }
+        directory
+    }
+
+    /// Create a directory of every player on the snapshot's leaderboards.
+    pub fn from_snapshot(snapshot: &LeaderboardSnapshot) -> Self {
+        Self::new(snapshot.categories.values().flatten().map(|entry| entry.name.clone()))
+    }
+
+    /// Add a player to the directory, does nothing if they're already in it.
+    pub fn insert(&mut self, name: String) {
+        let entry = (name.to_lowercase(), name);
+        let position = self.names.partition_point(|other| *other < entry);
+        if self.names.get(position) != Some(&entry) {
+            self.names.insert(position, entry);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|(_, name)| name.as_str())
+    }
+
+    /// Find a player by their name ignoring case and return the name as it's spelled in the game.
+    /// If several players' names only differ by case the first one in byte order is returned.
+    pub fn lookup(&self, name: &str) -> Option<&str> {
+        let lowercase = name.to_lowercase();
+        let position = self.names.partition_point(|(other, _)| *other < lowercase);
+        let (candidate_lowercase, candidate) = self.names.get(position)?;
+        (*candidate_lowercase == lowercase).then_some(candidate.as_str())
+    }
+
+    /// Find up to `limit` players whose name matches the query ignoring case, best matches first.
+    /// Exact matches come first, then names starting with the query, then names containing it
+    /// and finally names containing the query's characters in order (`jmpl` matches `JumpyLion`).
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&str> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<(u8, &str)> = self.names.iter()
+            .filter_map(|(lowercase, name)| Self::match_score(&query, lowercase).map(|score| (score, name.as_str())))
+            .collect();
+        matches.sort_by(|(score, name), (other_score, other_name)| {
+            score.cmp(other_score)
+                .then(name.len().cmp(&other_name.len()))
+                .then(name.cmp(other_name))
+        });
+        matches.into_iter().take(limit).map(|(_, name)| name).collect()
+    }
+
+    fn match_score(query: &str, name: &str) -> Option<u8> {
+        if name == query {
+            Some(0)
+        }
+        else if name.starts_with(query) {
+            Some(1)
+        }
+        else if name.contains(query) {
+            Some(2)
+        }
+        else {
+            let mut characters = name.chars();
+            query.chars()
+                .all(|query_character| characters.any(|character| character == query_character))
+                .then_some(3)
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::{collections::{BTreeMap, HashMap}, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LeaderboardCategory, Leaderboards, LeaderboardsEntry};
+
+/// All the leaderboards captured at one instant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardSnapshot {
+    pub taken_at: SystemTime,
+    /// The entries of every leaderboard that was captured, ordered by rank.
+    pub categories: BTreeMap<LeaderboardCategory, Vec<SnapshotEntry>>
+}
+
+/// A single player's position on a leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub rank: usize,
+    pub name: String,
+    /// The leaderboard's value, for the highest levels leaderboard this is the level.
+    pub value: usize
+}
+
+impl LeaderboardSnapshot {
+    /// Create a snapshot from leaderboards data, leaderboards that weren't requested are left out.
+    pub fn from_leaderboards(leaderboards: Leaderboards, taken_at: SystemTime) -> Self {
+        let mut categories: BTreeMap<LeaderboardCategory, Vec<SnapshotEntry>> = BTreeMap::new();
+        let entries = [
+            (LeaderboardCategory::Credits, leaderboards.credits),
+            (LeaderboardCategory::CombatsWon, leaderboards.combats_won),
+            (LeaderboardCategory::ItemsCrafted, leaderboards.items_crafted),
+            (LeaderboardCategory::JobsPerformed, leaderboards.jobs_performed),
+            (LeaderboardCategory::Overdoses, leaderboards.overdoses),
+            (LeaderboardCategory::MissionsCompleted, leaderboards.missions_completed),
+        ];
+        for (category, entries) in entries {
+            if let Some(entries) = entries {
+                categories.insert(category, entries.into_iter().map(SnapshotEntry::from).collect());
+            }
+        }
+        if let Some(entries) = leaderboards.highest_levels {
+            let entries = entries.into_iter()
+                .map(|entry| SnapshotEntry { rank: entry.rank, name: entry.name, value: entry.level })
+                .collect();
+            categories.insert(LeaderboardCategory::HighestLevels, entries);
+        }
+        for entries in categories.values_mut() {
+            entries.sort_by_key(|entry| entry.rank);
+        }
+        Self { taken_at, categories }
+    }
+
+    /// The entries of a leaderboard, empty if it wasn't captured.
+    pub fn entries(&self, category: LeaderboardCategory) -> &[SnapshotEntry] {
+        self.categories.get(&category).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Find a player's entry on a leaderboard.
+    pub fn entry(&self, category: LeaderboardCategory, name: &str) -> Option<&SnapshotEntry> {
+        self.entries(category).iter().find(|entry| entry.name == name)
+    }
+
+    /// Compare this snapshot to a newer one.
+    /// Only leaderboards that were captured in both snapshots are compared.
+    pub fn diff(&self, newer: &LeaderboardSnapshot) -> SnapshotDiff {
+        let mut categories = BTreeMap::new();
+        for (category, old_entries) in &self.categories {
+            if let Some(new_entries) = newer.categories.get(category) {
+                categories.insert(*category, CategoryDiff::new(old_entries, new_entries));
+            }
+        }
+        SnapshotDiff { from: self.taken_at, to: newer.taken_at, categories }
+    }
+}
+
+impl From<LeaderboardsEntry> for SnapshotEntry {
+    fn from(entry: LeaderboardsEntry) -> Self {
+        Self { rank: entry.rank, name: entry.name, value: entry.value }
+    }
+}
+
+/// The differences between two snapshots, see [`LeaderboardSnapshot::diff`].
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff {
+    pub from: SystemTime,
+    pub to: SystemTime,
+    pub categories: BTreeMap<LeaderboardCategory, CategoryDiff>
+}
+
+impl SnapshotDiff {
+    /// The differences on a single leaderboard.
+    pub fn category(&self, category: LeaderboardCategory) -> Option<&CategoryDiff> {
+        self.categories.get(&category)
+    }
+}
+
+/// The differences on a single leaderboard.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryDiff {
+    /// Players that are on both snapshots and whose rank or value changed, ordered by their new rank.
+    pub changes: Vec<EntryChange>,
+    /// Players that are only on the newer snapshot.
+    pub new_entrants: Vec<SnapshotEntry>,
+    /// Players that are only on the older snapshot.
+    pub drop_outs: Vec<SnapshotEntry>
+}
+
+impl CategoryDiff {
+    fn new(old_entries: &[SnapshotEntry], new_entries: &[SnapshotEntry]) -> Self {
+        let old_by_name: HashMap<&str, &SnapshotEntry> = old_entries.iter()
+            .map(|entry| (entry.name.as_str(), entry))
+            .collect();
+        let new_by_name: HashMap<&str, &SnapshotEntry> = new_entries.iter()
+            .map(|entry| (entry.name.as_str(), entry))
+            .collect();
+
+        let mut diff = CategoryDiff::default();
+        for new_entry in new_entries {
+            match old_by_name.get(new_entry.name.as_str()) {
+                Some(old_entry) => {
+                    if old_entry.rank != new_entry.rank || old_entry.value != new_entry.value {
+                        diff.changes.push(EntryChange {
+                            name: new_entry.name.clone(),
+                            old_rank: old_entry.rank,
+                            new_rank: new_entry.rank,
+                            old_value: old_entry.value,
+                            new_value: new_entry.value
+                        });
+                    }
+                },
+                None => diff.new_entrants.push(new_entry.clone())
+            }
+        }
+        diff.drop_outs = old_entries.iter()
+            .filter(|entry| !new_by_name.contains_key(entry.name.as_str()))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// The changes ordered by how many ranks the players moved, biggest first.
+    pub fn biggest_movers(&self) -> Vec<&EntryChange> {
+        let mut changes: Vec<&EntryChange> = self.changes.iter().collect();
+        changes.sort_by_key(|change| std::cmp::Reverse(change.rank_change().unsigned_abs()));
+        changes
+    }
+
+    /// The changes ordered by how much the players' values grew, biggest first.
+    pub fn biggest_gains(&self) -> Vec<&EntryChange> {
+        let mut changes: Vec<&EntryChange> = self.changes.iter().collect();
+        changes.sort_by_key(|change| std::cmp::Reverse(change.value_delta()));
+        changes
+    }
+}
+
+/// A player whose position changed between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryChange {
+    pub name: String,
+    pub old_rank: usize,
+    pub new_rank: usize,
+    pub old_value: usize,
+    pub new_value: usize
+}
+
+impl EntryChange {
+    /// How many ranks the player climbed, negative if they fell.
+    pub fn rank_change(&self) -> isize {
+        self.old_rank as isize - self.new_rank as isize
+    }
+
+    /// How much the player's value changed.
+    pub fn value_delta(&self) -> isize {
+        self.new_value as isize - self.old_value as isize
+    }
+}
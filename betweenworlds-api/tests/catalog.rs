@@ -0,0 +1,71 @@
+mod common;
+
+use betweenworlds_api::{ConsumeEffect, Item, ItemCatalog};
+use serde_json::{json, Value};
+
+use common::item_with;
+
+fn catalog() -> ItemCatalog {
+    let restore_energy = json!([{"type": 2, "min": 10, "max": 20}]);
+    ItemCatalog::new(vec![
+        item_with("Energy Drink", 5, 3, 10, restore_energy.clone(), "A fizzy can of sugar"),
+        item_with("Energy Bar", 25, 3, 30, restore_energy, "A dense bar of oats"),
+        item_with("Bandage", 1, 3, 5, json!([{"type": 1, "min": 5, "max": 10}]), "Stops the bleeding"),
+        item_with("Rusty Sword", 10, 1, 50, Value::Null, "An old sword covered in rust"),
+        item_with("Empty Can", 1, 2, 1, json!([]), "A crushed can")
+    ])
+}
+
+fn names(items: Vec<&Item>) -> Vec<&str> {
+    items.into_iter().map(|item| item.name.as_str()).collect()
+}
+
+#[test]
+fn consumable_restoring_energy_below_level_20() {
+    let catalog = catalog();
+    let items = catalog.query().consumable().consume_effect_kind("RestoreEnergy").level(..20).collect();
+    assert_eq!(names(items), ["Energy Drink"]);
+}
+
+#[test]
+fn consume_effect_kind() {
+    let catalog = catalog();
+    assert_eq!(names(catalog.query().consume_effect_kind("restoreenergy").collect()), ["Energy Drink", "Energy Bar"]);
+    assert_eq!(names(catalog.query().consume_effect_kind("RestoreHealth").collect()), ["Bandage"]);
+    assert!(catalog.query().consume_effect_kind("NotAnEffect").collect().is_empty());
+    let predicate = catalog.query().consume_effect(|effect| matches!(effect, ConsumeEffect::RestoreHealth(_))).collect();
+    assert_eq!(names(predicate), ["Bandage"]);
+}
+
+#[test]
+fn consumable() {
+    let catalog = catalog();
+    assert_eq!(names(catalog.query().consumable().collect()), ["Energy Drink", "Energy Bar", "Bandage"]);
+}
+
+#[test]
+fn level_type_and_worth() {
+    let catalog = catalog();
+    assert_eq!(names(catalog.query().level(10..=25).collect()), ["Energy Bar", "Rusty Sword"]);
+    assert_eq!(names(catalog.query().item_type(1).collect()), ["Rusty Sword"]);
+    assert_eq!(names(catalog.query().worth(10..50).collect()), ["Energy Drink", "Energy Bar"]);
+    assert_eq!(catalog.query().item_type(3).worth(..10).count(), 1);
+}
+
+#[test]
+fn get_ignores_case() {
+    let catalog = catalog();
+    assert_eq!(catalog.len(), 5);
+    assert_eq!(catalog.get("energy drink").map(|item| item.level), Some(5));
+    assert_eq!(catalog.get("RUSTY SWORD").map(|item| item.name.as_str()), Some("Rusty Sword"));
+    assert!(catalog.get("Rusty").is_none());
+}
+
+#[test]
+fn search_ignores_case_and_needs_every_word() {
+    let catalog = catalog();
+    assert_eq!(names(catalog.search("CAN")), ["Energy Drink", "Empty Can"]);
+    assert_eq!(names(catalog.search("can Sugar")), ["Energy Drink"]);
+    assert_eq!(names(catalog.search("sword rust")), ["Rusty Sword"]);
+    assert!(catalog.search("sword sugar").is_empty());
+}
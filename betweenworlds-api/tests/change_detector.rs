@@ -0,0 +1,51 @@
+use betweenworlds_api::{ChangeDetector, Observation};
+
+fn changed(observation: Observation) -> ChangeDetector {
+    match observation {
+        Observation::Changed(detector) => detector,
+        Observation::Unchanged => panic!("The response should have changed.")
+    }
+}
+
+#[test]
+fn first_response_changed() {
+    let detector = ChangeDetector::new();
+    assert_eq!(detector.etag(), None);
+    assert_eq!(detector.last_modified(), None);
+    let next = changed(detector.observe(200, Some("\"v1\"".to_string()), Some("Mon, 01 Jan 2024 00:00:00 GMT".to_string()), "[]"));
+    assert_eq!(next.etag(), Some("\"v1\""));
+    assert_eq!(next.last_modified(), Some("Mon, 01 Jan 2024 00:00:00 GMT"));
+}
+
+#[test]
+fn not_modified_is_unchanged() {
+    let detector = changed(ChangeDetector::new().observe(200, Some("\"v1\"".to_string()), None, "[1]"));
+    assert_eq!(detector.observe(304, None, None, ""), Observation::Unchanged);
+    // Even a detector that never saw a response trusts the server.
+    assert_eq!(ChangeDetector::new().observe(304, None, None, ""), Observation::Unchanged);
+}
+
+#[test]
+fn same_body_is_unchanged() {
+    // Servers that ignore the validators answer with the whole body again.
+    let detector = changed(ChangeDetector::new().observe(200, None, None, "[1]"));
+    assert_eq!(detector.observe(200, None, None, "[1]"), Observation::Unchanged);
+    assert_eq!(detector.observe(200, Some("\"v2\"".to_string()), None, "[1]"), Observation::Unchanged);
+}
+
+#[test]
+fn different_body_changed() {
+    let detector = changed(ChangeDetector::new().observe(200, Some("\"v1\"".to_string()), None, "[1]"));
+    let next = changed(detector.observe(200, Some("\"v2\"".to_string()), None, "[2]"));
+    assert_eq!(next.etag(), Some("\"v2\""));
+    assert_eq!(next.observe(200, None, None, "[2]"), Observation::Unchanged);
+    assert_ne!(next.observe(200, None, None, "[1]"), Observation::Unchanged);
+}
+
+#[test]
+fn observing_leaves_the_detector_as_is() {
+    let detector = ChangeDetector::new();
+    let _ = detector.observe(200, Some("\"v1\"".to_string()), None, "[1]");
+    assert_eq!(detector, ChangeDetector::new());
+    assert!(matches!(detector.observe(200, None, None, "[1]"), Observation::Changed(_)));
+}
@@ -0,0 +1,51 @@
+//! Items and users shared by the tests, the valuation and networth tests use them too.
+// Every test file only uses some of the fixtures.
+#![allow(dead_code)]
+
+use betweenworlds_api::{Item, User};
+use serde_json::{json, Value};
+
+/// An item of level 1 and type 0 without a description, `consume_effects` is the api's json for them.
+pub fn item(name: &str, worth_multiplier: usize, consume_effects: Value) -> Item {
+    item_with(name, 1, 0, worth_multiplier, consume_effects, "")
+}
+
+/// An item with a level, a type and the description of its lowest quality.
+pub fn item_with(name: &str, level: usize, item_type: usize, worth_multiplier: usize, consume_effects: Value, description: &str) -> Item {
+    serde_json::from_value(json!({
+        "qualityAdjectives": ["Worn", "Used", "Standard", "Fine", "Pristine"],
+        "level": level,
+        "imageUrl": "",
+        "type": item_type,
+        "name": name,
+        "worthMultiplier": worth_multiplier,
+        "consumeEffects": consume_effects,
+        "skillEffects": null,
+        "qualityDescriptions": [description, "", "", "", ""]
+    })).unwrap()
+}
+
+/// A user with equipment as (name, quality) and inventory stacks as (name, quality, quantity),
+/// `None` when they weren't requested.
+pub fn user(name: &str, equipment: Option<&[(&str, u8)]>, inventory: Option<&[(&str, u8, usize)]>) -> User {
+    let equipment = equipment.map(|equipment| equipment.iter()
+        .map(|(name, quality)| json!({"itemName": name, "quality": quality}))
+        .collect::<Vec<_>>());
+    let inventory = inventory.map(|inventory| inventory.iter()
+        .map(|(name, quality, quantity)| json!({
+            "itemName": name,
+            "moduleSlots": 0,
+            "quality": quality,
+            "modules": [],
+            "quantity": quantity
+        }))
+        .collect::<Vec<_>>());
+    serde_json::from_value(json!({
+        "biography": null,
+        "createdAt": "2023-01-01T00:00:00.000Z",
+        "equipment": equipment,
+        "inventory": inventory,
+        "roles": [],
+        "name": name
+    })).unwrap()
+}
@@ -0,0 +1,31 @@
+use betweenworlds_api::{ApiError, CredentialsStatus};
+
+#[test]
+fn failed_requests() {
+    assert!(matches!(ApiError::of_failure(true, false, None), ApiError::RequestTimeout));
+    assert!(matches!(ApiError::of_failure(false, true, None), ApiError::Unreachable));
+    assert!(matches!(ApiError::of_failure(false, false, Some(401)), ApiError::Unauthorized));
+    assert!(matches!(ApiError::of_failure(false, false, Some(403)), ApiError::Unauthorized));
+    assert!(matches!(ApiError::of_failure(false, false, Some(404)), ApiError::NotFound));
+    assert!(matches!(ApiError::of_failure(false, false, Some(500)), ApiError::Other));
+    assert!(matches!(ApiError::of_failure(false, false, None), ApiError::Other));
+}
+
+#[test]
+fn a_timeout_wins_over_the_status() {
+    assert!(matches!(ApiError::of_failure(true, false, Some(401)), ApiError::RequestTimeout));
+}
+
+#[test]
+fn credentials_status() {
+    assert_eq!(CredentialsStatus::of(Ok(())).unwrap(), CredentialsStatus::Valid);
+    assert_eq!(CredentialsStatus::of::<()>(Err(ApiError::Unauthorized)).unwrap(), CredentialsStatus::Unauthorized);
+    assert_eq!(CredentialsStatus::of::<()>(Err(ApiError::Unreachable)).unwrap(), CredentialsStatus::Unreachable);
+    assert_eq!(CredentialsStatus::of::<()>(Err(ApiError::RequestTimeout)).unwrap(), CredentialsStatus::Unreachable);
+}
+
+#[test]
+fn other_errors_say_nothing_about_the_credentials() {
+    assert!(matches!(CredentialsStatus::of::<()>(Err(ApiError::NotFound)), Err(ApiError::NotFound)));
+    assert!(matches!(CredentialsStatus::of::<()>(Err(ApiError::Other)), Err(ApiError::Other)));
+}
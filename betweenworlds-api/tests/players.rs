@@ -0,0 +1,71 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use betweenworlds_api::{LeaderboardCategory, LeaderboardSnapshot, PlayerDirectory, SnapshotEntry};
+
+fn directory(names: &[&str]) -> PlayerDirectory {
+    PlayerDirectory::new(names.iter().map(|name| name.to_string()))
+}
+
+#[test]
+fn insert_ignores_duplicates() {
+    let mut directory = directory(&["Foo", "foo", "Bar"]);
+    directory.insert("Foo".to_string());
+    directory.insert("foo".to_string());
+    directory.insert("Bar".to_string());
+    assert_eq!(directory.len(), 3);
+    assert_eq!(directory.names().collect::<Vec<_>>(), ["Bar", "Foo", "foo"]);
+}
+
+#[test]
+fn insert_keeps_names_differing_by_case() {
+    let mut directory = PlayerDirectory::default();
+    for name in ["foo", "FOO", "Foo", "foo", "FOO", "Foo"] {
+        directory.insert(name.to_string());
+    }
+    assert_eq!(directory.names().collect::<Vec<_>>(), ["FOO", "Foo", "foo"]);
+}
+
+#[test]
+fn from_snapshot() {
+    let entry = |name: &str| SnapshotEntry { rank: 1, name: name.to_string(), value: 1 };
+    let snapshot = LeaderboardSnapshot {
+        taken_at: SystemTime::UNIX_EPOCH,
+        categories: BTreeMap::from([
+            (LeaderboardCategory::Credits, vec![entry("a"), entry("b")]),
+            (LeaderboardCategory::Overdoses, vec![entry("b"), entry("c")])
+        ])
+    };
+    assert_eq!(PlayerDirectory::from_snapshot(&snapshot).names().collect::<Vec<_>>(), ["a", "b", "c"]);
+}
+
+#[test]
+fn lookup_ignores_case() {
+    let directory = directory(&["JumpyLion", "Bob"]);
+    assert_eq!(directory.lookup("jumpylion"), Some("JumpyLion"));
+    assert_eq!(directory.lookup("BOB"), Some("Bob"));
+    assert_eq!(directory.lookup("Jumpy"), None);
+    assert_eq!(directory.lookup("Zed"), None);
+}
+
+#[test]
+fn search_ranks_exact_prefix_substring_then_subsequence() {
+    let directory = directory(&["JumpyLion", "Lion", "Lionheart", "SeaLion", "Lillian", "Bob"]);
+    assert_eq!(directory.search("lion", 10), ["Lion", "Lionheart", "SeaLion", "JumpyLion"]);
+    assert_eq!(directory.search("jmpl", 10), ["JumpyLion"]);
+    assert_eq!(directory.search("ln", 10), ["Lion", "Lillian", "SeaLion", "JumpyLion", "Lionheart"]);
+}
+
+#[test]
+fn search_ties_shorter_names_first_then_alphabetical() {
+    let directory = directory(&["abcd", "abd", "abc"]);
+    assert_eq!(directory.search("ab", 10), ["abc", "abd", "abcd"]);
+}
+
+#[test]
+fn search_limit_and_empty_query() {
+    let directory = directory(&["Lion", "Lionheart", "SeaLion"]);
+    assert_eq!(directory.search("LION", 2), ["Lion", "Lionheart"]);
+    assert!(directory.search("lion", 0).is_empty());
+    assert!(directory.search("", 10).is_empty());
+    assert!(directory.search("xyz", 10).is_empty());
+}
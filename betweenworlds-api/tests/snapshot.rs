@@ -0,0 +1,91 @@
+use std::{collections::BTreeMap, time::{Duration, SystemTime}};
+
+use betweenworlds_api::{LeaderboardCategory, LeaderboardSnapshot, Leaderboards, SnapshotEntry};
+use serde_json::json;
+
+/// A credits leaderboard snapshot from (name, value) pairs, ranked in order.
+fn snapshot(entries: &[(&str, usize)], seconds: u64) -> LeaderboardSnapshot {
+    let entries = entries.iter()
+        .enumerate()
+        .map(|(index, (name, value))| SnapshotEntry { rank: index + 1, name: name.to_string(), value: *value })
+        .collect();
+    LeaderboardSnapshot {
+        taken_at: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+        categories: BTreeMap::from([(LeaderboardCategory::Credits, entries)])
+    }
+}
+
+fn names(entries: &[SnapshotEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.name.as_str()).collect()
+}
+
+#[test]
+fn from_leaderboards() {
+    let leaderboards: Leaderboards = serde_json::from_value(json!({
+        "credits": [
+            {"rank": 2, "credits": 50, "name": "b", "roles": []},
+            {"rank": 1, "credits": 90, "name": "a", "roles": []}
+        ],
+        "highestLevels": [{"rank": 1, "level": 12, "expCurrent": 5, "name": "a", "roles": []}]
+    })).unwrap();
+    let snapshot = LeaderboardSnapshot::from_leaderboards(leaderboards, SystemTime::UNIX_EPOCH);
+    assert_eq!(names(snapshot.entries(LeaderboardCategory::Credits)), ["a", "b"]);
+    assert_eq!(snapshot.entry(LeaderboardCategory::HighestLevels, "a").map(|entry| entry.value), Some(12));
+    assert!(snapshot.entries(LeaderboardCategory::Overdoses).is_empty());
+}
+
+#[test]
+fn new_entrants_and_drop_outs() {
+    let older = snapshot(&[("a", 30), ("b", 20), ("c", 10)], 0);
+    let newer = snapshot(&[("a", 30), ("d", 25), ("b", 20)], 60);
+    let diff = older.diff(&newer);
+    assert_eq!(diff.from, older.taken_at);
+    assert_eq!(diff.to, newer.taken_at);
+    let credits = diff.category(LeaderboardCategory::Credits).unwrap();
+    assert_eq!(names(&credits.new_entrants), ["d"]);
+    assert_eq!(names(&credits.drop_outs), ["c"]);
+}
+
+#[test]
+fn unchanged_players_are_left_out() {
+    let older = snapshot(&[("a", 30), ("b", 20)], 0);
+    let diff = older.diff(&snapshot(&[("a", 30), ("b", 20)], 60));
+    let credits = diff.category(LeaderboardCategory::Credits).unwrap();
+    assert!(credits.changes.is_empty());
+    assert!(credits.new_entrants.is_empty());
+    assert!(credits.drop_outs.is_empty());
+}
+
+#[test]
+fn rank_changes_and_value_deltas() {
+    let older = snapshot(&[("a", 30), ("b", 20), ("c", 10)], 0);
+    let newer = snapshot(&[("c", 40), ("a", 35), ("b", 20)], 60);
+    let credits = older.diff(&newer).category(LeaderboardCategory::Credits).unwrap().clone();
+    let changes: Vec<_> = credits.changes.iter()
+        .map(|change| (change.name.as_str(), change.rank_change(), change.value_delta()))
+        .collect();
+    // Ordered by the new rank.
+    assert_eq!(changes, [("c", 2, 30), ("a", -1, 5), ("b", -1, 0)]);
+}
+
+#[test]
+fn biggest_movers_and_gains() {
+    let older = snapshot(&[("a", 50), ("b", 40), ("c", 30), ("d", 20)], 0);
+    let newer = snapshot(&[("d", 100), ("a", 52), ("c", 45), ("b", 10)], 60);
+    let credits = older.diff(&newer).category(LeaderboardCategory::Credits).unwrap().clone();
+    let movers: Vec<_> = credits.biggest_movers().iter().map(|change| change.name.as_str()).collect();
+    // d climbed 3 ranks, then a and b moved 1 and 2, c didn't move but gained.
+    assert_eq!(movers, ["d", "b", "a", "c"]);
+    let gains: Vec<_> = credits.biggest_gains().iter().map(|change| (change.name.as_str(), change.value_delta())).collect();
+    assert_eq!(gains, [("d", 80), ("c", 15), ("a", 2), ("b", -30)]);
+}
+
+#[test]
+fn only_categories_in_both_snapshots_are_compared() {
+    let older = snapshot(&[("a", 30)], 0);
+    let mut newer = snapshot(&[("a", 31)], 60);
+    newer.categories.insert(LeaderboardCategory::Overdoses, Vec::new());
+    let diff = older.diff(&newer);
+    assert!(diff.category(LeaderboardCategory::Credits).is_some());
+    assert!(diff.category(LeaderboardCategory::Overdoses).is_none());
+}
@@ -0,0 +1,181 @@
+//! Values players' accounts: their equipment, inventory and credits.
+
+use betweenworlds_api::{ConsumeEffect, Item, ItemCatalog, User};
+
+mod prices;
+mod ranking;
+mod report;
+mod snapshot;
+
+pub use prices::PriceSheet;
+pub use ranking::{Ranking, RankingEntry};
+pub use report::{NetworthReport, ReportLine, UnvaluedLine, Category, ValueSource};
+pub use snapshot::{NetworthSnapshot, NetworthDiff, ItemChange, ChangeKind};
+
+/// Values items and accounts using the items of a catalog.
+pub struct Valuator<'a> {
+    catalog: &'a ItemCatalog,
+    prices: Option<&'a PriceSheet>
+}
+
+impl<'a> Valuator<'a> {
+    pub fn new(catalog: &'a ItemCatalog) -> Self {
+        Self { catalog, prices: None }
+    }
+
+    /// Value the items of the sheet at its prices instead of computing their value.
+    pub fn with_prices(mut self, prices: &'a PriceSheet) -> Self {
+        self.prices = Some(prices);
+        self
+    }
+
+    /// The value of a single item at a quality: its price in the price sheet if it has one, otherwise the price it sells for or,
+    /// if it's strictly more, the credits consuming it gives on average rounded to the nearest credit.
+    pub fn item_value(&self, item: &Item, quality: u8) -> ItemValue {
+        self.item_value_on(item, quality, &mut Vec::new())
+    }
+
+    /// The credits consuming an item at a quality gives on average, negative if consuming it costs credits.
+    ///
+    /// `AddCredits` gives the middle of its range plus its worth multiplier scaled by the quality
+    /// the same way sell prices are, `RemoveCredits` takes the middle of its range.
+    /// `AddItem` gives its chance times the average quantity times the value of the dropped item
+    /// at the average quality rounded to the nearest quality, the dropped item is valued the same way
+    /// so loot that drops loot adds up. An item that drops one of the items it was dropped by
+    /// only counts that item's price in the price sheet or its sell price, and items missing from the catalog are worth nothing.
+    /// Restoring, buffing or lowering stats, missions and effects the api doesn't know about are worth nothing.
+    pub fn consume_value(&self, item: &Item, quality: u8) -> f64 {
+        self.consume_value_on(item, quality, &mut Vec::new())
+    }
+
+    /// `path` holds the lowercase names of the items being consumed to get to this one.
+    fn item_value_on(&self, item: &Item, quality: u8, path: &mut Vec<String>) -> ItemValue {
+        if let Some(price) = self.price_override(item, quality) {
+            return ItemValue { value: price, source: ValueSource::Override };
+        }
+        let sell_price = sell_price(item, quality);
+        let consume_value = self.consume_value_on(item, quality, path);
+        if consume_value > sell_price as f64 {
+            ItemValue { value: consume_value.round() as usize, source: ValueSource::Consume }
+        }
+        else {
+            ItemValue { value: sell_price, source: ValueSource::SellPrice }
+        }
+    }
+
+    fn price_override(&self, item: &Item, quality: u8) -> Option<usize> {
+        self.prices.and_then(|prices| prices.get(&item.name, quality))
+    }
+
+    fn consume_value_on(&self, item: &Item, quality: u8, path: &mut Vec<String>) -> f64 {
+        path.push(item.name.to_lowercase());
+        let value = item.consume_effects.iter()
+            .flatten()
+            .map(|effect| self.effect_value(effect, quality, path))
+            .sum();
+        path.pop();
+        value
+    }
+
+    fn effect_value(&self, effect: &ConsumeEffect, quality: u8, path: &mut Vec<String>) -> f64 {
+        match effect {
+            ConsumeEffect::AddCredits(effect) => {
+                let worth = effect.worth_multiplier.unwrap_or(0) * quality_multiplier(quality);
+                average(effect.min as f64, effect.max as f64) + worth as f64
+            },
+            ConsumeEffect::RemoveCredits(effect) => -average(effect.min as f64, effect.max as f64),
+            ConsumeEffect::AddItem(effect) => {
+                let Some(item) = self.catalog.get(&effect.item_name) else {
+                    return 0.0;
+                };
+                let quality = average(effect.quality_min as f64, effect.quality_max as f64).round() as u8;
+                let value = if path.contains(&item.name.to_lowercase()) {
+                    self.price_override(item, quality).unwrap_or_else(|| sell_price(item, quality))
+                }
+                else {
+                    self.item_value_on(item, quality, path).value
+                };
+                let chance = effect.chance.clamp(0.0, 1.0) as f64;
+                chance * average(effect.quantity_min as f64, effect.quantity_max as f64) * value as f64
+            },
+            ConsumeEffect::Unknown
+            | ConsumeEffect::RestoreHealth(_)
+            | ConsumeEffect::RestoreEnergy(_)
+            | ConsumeEffect::RestoreSpirit(_)
+            | ConsumeEffect::BuffHealth(_)
+            | ConsumeEffect::BuffEnergy(_)
+            | ConsumeEffect::BuffSpirit(_)
+            | ConsumeEffect::DecreaseHealth(_)
+            | ConsumeEffect::DecreaseEnergy(_)
+            | ConsumeEffect::DecreaseSpirit(_)
+            | ConsumeEffect::DebuffHealth(_)
+            | ConsumeEffect::DebuffEnergy(_)
+            | ConsumeEffect::DebuffSpirit(_)
+            | ConsumeEffect::AcceptMission(_)
+            | ConsumeEffect::Unrecognized { .. } => 0.0
+        }
+    }
+
+    /// Value a player's equipment and inventory.
+    /// The user has to be requested with the equipment and inventory flags, the ones that are missing are left out of the report.
+    /// `credits` are the player's credits from the leaderboards, they're added to the total as is.
+    /// Items missing from the catalog can't be valued, they're listed apart and the report is marked as partial.
+    pub fn value(&self, user: &User, credits: Option<usize>) -> NetworthReport {
+        let mut lines = Vec::new();
+        let mut unvalued = Vec::new();
+        let equipment = user.equipment.iter().flatten().map(|item| (&item.item_name, item.quality, 1, Category::Equipment));
+        let inventory = user.inventory.iter().flatten().map(|item| (&item.item_name, item.quality, item.quantity, Category::Inventory));
+        for (item_name, quality, quantity, category) in equipment.chain(inventory) {
+            match self.catalog.get(item_name) {
+                Some(item) => lines.push(self.line(item, quality, quantity, category)),
+                None => unvalued.push(UnvaluedLine { item_name: item_name.clone(), quality, quantity, category })
+            }
+        }
+        NetworthReport::new(
+            user.name.clone(),
+            lines,
+            unvalued,
+            user.equipment.is_some(),
+            user.inventory.is_some(),
+            credits
+        )
+    }
+
+    fn line(&self, item: &Item, quality: u8, quantity: usize, category: Category) -> ReportLine {
+        let ItemValue { value: unit_value, source } = self.item_value(item, quality);
+        ReportLine {
+            item_name: item.name.clone(),
+            quality,
+            quality_adjective: item.quality_adjectives.get(quality as usize).cloned().unwrap_or_default(),
+            quantity,
+            unit_value,
+            line_total: unit_value * quantity,
+            value_source: source,
+            // The report fills the share in once it knows the total.
+            share: 0.0,
+            category
+        }
+    }
+}
+
+/// The value of a single item, see [`Valuator::item_value`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemValue {
+    pub value: usize,
+    pub source: ValueSource
+}
+
+/// The price an item sells for at a quality.
+pub fn sell_price(item: &Item, quality: u8) -> usize {
+    item.worth_multiplier * quality_multiplier(quality)
+}
+
+/// Prices grow linearly with the quality, an item of the lowest quality (0) is worth its worth multiplier.
+fn quality_multiplier(quality: u8) -> usize {
+    quality as usize + 1
+}
+
+/// The middle of a uniformly distributed range.
+fn average(min: f64, max: f64) -> f64 {
+    (min + max) / 2.0
+}
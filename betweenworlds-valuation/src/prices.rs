@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use betweenworlds_api::ItemCatalog;
+
+/// Prices set by hand that replace the computed value of items, e.g. their market prices.
+/// A price is either for a single quality of an item or for all its qualities,
+/// the price of a single quality wins.
+#[derive(Debug, Clone, Default)]
+pub struct PriceSheet {
+    /// Lowercase item name to its prices.
+    prices: HashMap<String, ItemPrices>
+}
+
+#[derive(Debug, Clone, Default)]
+struct ItemPrices {
+    /// The name as it was written, to report it.
+    item_name: String,
+    all: Option<usize>,
+    by_quality: HashMap<u8, usize>
+}
+
+impl PriceSheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// Set the price of an item ignoring case, for a single quality or all of them if `quality` is `None`.
+    pub fn set(&mut self, item_name: &str, quality: Option<u8>, price: usize) {
+        let prices = self.prices.entry(item_name.to_lowercase()).or_insert_with(|| ItemPrices {
+            item_name: item_name.to_string(),
+            ..ItemPrices::default()
+        });
+        match quality {
+            Some(quality) => {
+                prices.by_quality.insert(quality, price);
+            },
+            None => prices.all = Some(price)
+        }
+    }
+
+    /// The price of an item at a quality ignoring case, if the sheet has one.
+    pub fn get(&self, item_name: &str, quality: u8) -> Option<usize> {
+        let prices = self.prices.get(&item_name.to_lowercase())?;
+        prices.by_quality.get(&quality).copied().or(prices.all)
+    }
+
+    /// The names of the items with a price that aren't in the catalog, sorted.
+    /// They're usually typos since their prices are never used.
+    pub fn unknown_items(&self, catalog: &ItemCatalog) -> Vec<&str> {
+        let mut names: Vec<&str> = self.prices.values()
+            .filter(|prices| catalog.get(&prices.item_name).is_none())
+            .map(|prices| prices.item_name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+}
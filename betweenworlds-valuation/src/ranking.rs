@@ -0,0 +1,68 @@
+use serde::Serialize;
+
+use crate::NetworthReport;
+
+/// Players ranked by their networth and compared to the median of the group.
+#[derive(Debug, Clone, Serialize)]
+pub struct Ranking {
+    /// The richest player first.
+    pub entries: Vec<RankingEntry>,
+    /// The median networth of the group, the middle of the two middle players when there is an even number of them.
+    pub median: usize
+}
+
+/// A player of a [`Ranking`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankingEntry {
+    /// The player's place starting from 1, players with the same networth share their place.
+    pub rank: usize,
+    pub player: String,
+    pub equipment: Option<usize>,
+    pub inventory: Option<usize>,
+    pub credits: Option<usize>,
+    pub total: usize,
+    /// How much more the player is worth than the median, negative if they're worth less.
+    pub delta: i64,
+    /// Whether some of the player's items couldn't be valued.
+    pub partial: bool
+}
+
+impl Ranking {
+    pub fn new(reports: &[NetworthReport]) -> Self {
+        let mut reports: Vec<&NetworthReport> = reports.iter().collect();
+        reports.sort_by(|report, other| other.total.cmp(&report.total).then_with(|| report.player.cmp(&other.player)));
+        let median = median(&reports.iter().map(|report| report.total).collect::<Vec<_>>());
+        let mut entries: Vec<RankingEntry> = Vec::with_capacity(reports.len());
+        for (index, report) in reports.into_iter().enumerate() {
+            let rank = match entries.last() {
+                Some(previous) if previous.total == report.total => previous.rank,
+                _ => index + 1
+            };
+            entries.push(RankingEntry {
+                rank,
+                player: report.player.clone(),
+                equipment: report.equipment,
+                inventory: report.inventory,
+                credits: report.credits,
+                total: report.total,
+                delta: report.total as i64 - median as i64,
+                partial: report.partial
+            });
+        }
+        Self { entries, median }
+    }
+}
+
+/// The median of totals sorted from the highest, 0 if there are none.
+fn median(totals: &[usize]) -> usize {
+    let middle = totals.len() / 2;
+    if totals.is_empty() {
+        0
+    }
+    else if totals.len() % 2 == 1 {
+        totals[middle]
+    }
+    else {
+        (totals[middle - 1] + totals[middle]) / 2
+    }
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+/// The value of a player's account, see [`crate::Valuator::value`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworthReport {
+    pub player: String,
+    /// One line per equipped item and inventory stack, the most valuable first.
+    pub lines: Vec<ReportLine>,
+    /// The items that couldn't be valued because they aren't in the item catalog.
+    pub unvalued: Vec<UnvaluedLine>,
+    /// Whether some items couldn't be valued, the totals leave them out.
+    pub partial: bool,
+    /// The value of the equipment, `None` if the equipment wasn't available.
+    pub equipment: Option<usize>,
+    /// The value of the inventory, `None` if the inventory wasn't available.
+    pub inventory: Option<usize>,
+    /// The player's raw credits, `None` if they weren't available.
+    pub credits: Option<usize>,
+    /// The equipment, inventory and credits added together.
+    pub total: usize
+}
+
+impl NetworthReport {
+    pub(crate) fn new(
+        player: String,
+        mut lines: Vec<ReportLine>,
+        unvalued: Vec<UnvaluedLine>,
+        has_equipment: bool,
+        has_inventory: bool,
+        credits: Option<usize>
+    ) -> Self {
+        let category_total = |category: Category| lines.iter()
+            .filter(|line| line.category == category)
+            .map(|line| line.line_total)
+            .sum::<usize>();
+        let equipment = has_equipment.then(|| category_total(Category::Equipment));
+        let inventory = has_inventory.then(|| category_total(Category::Inventory));
+        let total = equipment.unwrap_or(0) + inventory.unwrap_or(0) + credits.unwrap_or(0);
+        for line in &mut lines {
+            line.share = if total == 0 {
+                0.0
+            }
+            else {
+                line.line_total as f64 / total as f64
+            };
+        }
+        lines.sort_by(|line, other| other.line_total.cmp(&line.line_total).then_with(|| line.item_name.cmp(&other.item_name)));
+        let partial = !unvalued.is_empty();
+        Self { player, lines, unvalued, partial, equipment, inventory, credits, total }
+    }
+
+    /// The `count` most valuable lines.
+    pub fn top(&self, count: usize) -> &[ReportLine] {
+        &self.lines[..usize::min(count, self.lines.len())]
+    }
+
+    /// The lines of a category.
+    pub fn lines_in(&self, category: Category) -> impl Iterator<Item = &ReportLine> {
+        self.lines.iter().filter(move |line| line.category == category)
+    }
+}
+
+/// The value of an equipped item or an inventory stack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportLine {
+    pub item_name: String,
+    pub quality: u8,
+    /// The item's name for the quality, e.g. `Pristine`.
+    pub quality_adjective: String,
+    pub quantity: usize,
+    /// The value of a single item.
+    pub unit_value: usize,
+    /// The value of the whole stack.
+    pub line_total: usize,
+    /// Where the unit value comes from.
+    pub value_source: ValueSource,
+    /// The fraction of the account's networth the stack is worth, between 0 and 1.
+    pub share: f64,
+    pub category: Category
+}
+
+/// An equipped item or inventory stack that isn't in the item catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnvaluedLine {
+    pub item_name: String,
+    pub quality: u8,
+    pub quantity: usize,
+    pub category: Category
+}
+
+/// Where an item's value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueSource {
+    /// The price the item sells for.
+    SellPrice,
+    /// The credits consuming the item gives on average, it's worth more than its sell price.
+    Consume,
+    /// The price set for the item in the price sheet.
+    Override
+}
+
+impl ValueSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueSource::SellPrice => "sell_price",
+            ValueSource::Consume => "consume",
+            ValueSource::Override => "override",
+        }
+    }
+}
+
+/// Where the item is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Equipment,
+    Inventory
+}
+
+impl Category {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Equipment => "equipment",
+            Category::Inventory => "inventory",
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use betweenworlds_api::ItemCatalog;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::NetworthReport;
+
+/// A player's networth report saved at one instant, to compare it to later ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworthSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub report: NetworthReport,
+    /// The lowercase names of the items of the report that can be consumed,
+    /// the diff uses them to tell consumed items from the ones that were sold or lost.
+    pub consumables: BTreeSet<String>
+}
+
+impl NetworthSnapshot {
+    pub fn new(report: NetworthReport, catalog: &ItemCatalog, taken_at: DateTime<Utc>) -> Self {
+        let names = report.lines.iter()
+            .map(|line| &line.item_name)
+            .chain(report.unvalued.iter().map(|line| &line.item_name));
+        let consumables = names
+            .filter(|name| catalog.get(name).is_some_and(|item| item.consume_effects.as_ref().is_some_and(|effects| !effects.is_empty())))
+            .map(|name| name.to_lowercase())
+            .collect();
+        Self { taken_at, report, consumables }
+    }
+
+    /// Compare this snapshot to a newer one.
+    /// Items are compared by name and quality wherever they are, moving an item between the equipment
+    /// and the inventory doesn't change anything. Unvalued items count as worth nothing.
+    /// Items that can't be consumed and went away while the credits rose count as sold.
+    pub fn diff(&self, newer: &NetworthSnapshot) -> NetworthDiff {
+        let credits_rose = matches!((self.report.credits, newer.report.credits), (Some(old), Some(new)) if new > old);
+        let old_items = holdings(&self.report);
+        let new_items = holdings(&newer.report);
+        let keys: BTreeSet<&(String, u8)> = old_items.keys().chain(new_items.keys()).collect();
+        let mut changes = Vec::new();
+        for key in keys {
+            let old = old_items.get(key).cloned().unwrap_or_default();
+            let new = new_items.get(key).cloned().unwrap_or_default();
+            if old.quantity == new.quantity && old.value == new.value {
+                continue;
+            }
+            let item_name = new.item_name.or(old.item_name).unwrap_or_default();
+            let kind = if new.quantity > old.quantity {
+                ChangeKind::Gained
+            }
+            else if new.quantity == old.quantity {
+                ChangeKind::Revalued
+            }
+            else if self.consumables.contains(&key.0) {
+                ChangeKind::Consumed
+            }
+            else if credits_rose {
+                ChangeKind::Sold
+            }
+            else {
+                ChangeKind::Lost
+            };
+            changes.push(ItemChange {
+                item_name,
+                quality: key.1,
+                kind,
+                old_quantity: old.quantity,
+                new_quantity: new.quantity,
+                old_value: old.value,
+                new_value: new.value
+            });
+        }
+        changes.sort_by(|change, other| other.value_delta().unsigned_abs().cmp(&change.value_delta().unsigned_abs())
+            .then_with(|| change.item_name.cmp(&other.item_name))
+            .then_with(|| change.quality.cmp(&other.quality)));
+        NetworthDiff {
+            player: newer.report.player.clone(),
+            from: self.taken_at,
+            to: newer.taken_at,
+            changes,
+            old_credits: self.report.credits,
+            new_credits: newer.report.credits,
+            old_total: self.report.total,
+            new_total: newer.report.total
+        }
+    }
+}
+
+/// How many of an item a player has and what they're worth.
+#[derive(Debug, Clone, Default)]
+struct Holding {
+    /// The name as it's written in the report.
+    item_name: Option<String>,
+    quantity: usize,
+    value: usize
+}
+
+/// The items of a report by lowercase name and quality.
+fn holdings(report: &NetworthReport) -> BTreeMap<(String, u8), Holding> {
+    let mut holdings: BTreeMap<(String, u8), Holding> = BTreeMap::new();
+    let lines = report.lines.iter()
+        .map(|line| (&line.item_name, line.quality, line.quantity, line.line_total))
+        .chain(report.unvalued.iter().map(|line| (&line.item_name, line.quality, line.quantity, 0)));
+    for (item_name, quality, quantity, value) in lines {
+        let holding = holdings.entry((item_name.to_lowercase(), quality)).or_default();
+        holding.item_name.get_or_insert_with(|| item_name.clone());
+        holding.quantity += quantity;
+        holding.value += value;
+    }
+    holdings
+}
+
+/// The differences between two snapshots of a player, see [`NetworthSnapshot::diff`].
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworthDiff {
+    pub player: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// The items whose quantity or value changed, the biggest change in value first.
+    pub changes: Vec<ItemChange>,
+    pub old_credits: Option<usize>,
+    pub new_credits: Option<usize>,
+    pub old_total: usize,
+    pub new_total: usize
+}
+
+impl NetworthDiff {
+    /// How much the networth changed.
+    pub fn total_delta(&self) -> i64 {
+        self.new_total as i64 - self.old_total as i64
+    }
+
+    /// How much the credits changed, `None` if either snapshot is missing them.
+    pub fn credits_delta(&self) -> Option<i64> {
+        Some(self.new_credits? as i64 - self.old_credits? as i64)
+    }
+
+    /// The changes of a kind.
+    pub fn changes_of(&self, kind: ChangeKind) -> impl Iterator<Item = &ItemChange> {
+        self.changes.iter().filter(move |change| change.kind == kind)
+    }
+}
+
+/// An item whose quantity or value changed between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemChange {
+    pub item_name: String,
+    pub quality: u8,
+    pub kind: ChangeKind,
+    pub old_quantity: usize,
+    pub new_quantity: usize,
+    /// The value of all the items in the older snapshot.
+    pub old_value: usize,
+    /// The value of all the items in the newer snapshot.
+    pub new_value: usize
+}
+
+impl ItemChange {
+    /// How many items were gained, negative if they were lost.
+    pub fn quantity_delta(&self) -> i64 {
+        self.new_quantity as i64 - self.old_quantity as i64
+    }
+
+    /// How much the value of the items changed.
+    pub fn value_delta(&self) -> i64 {
+        self.new_value as i64 - self.old_value as i64
+    }
+}
+
+/// Why an item changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The player has more of the item.
+    Gained,
+    /// The player has less of an item that can be consumed, it was probably consumed.
+    Consumed,
+    /// The player has less of an item that can't be consumed and their credits rose, it was probably sold.
+    /// The snapshots don't tell which items the credits came from, every item that went away counts as sold.
+    Sold,
+    /// The player has less of an item that can't be consumed and their credits didn't rise,
+    /// it was traded or dropped.
+    Lost,
+    /// The player has as many of the item but it's worth something else, e.g. after a price change.
+    Revalued
+}
+
+impl ChangeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeKind::Gained => "gained",
+            ChangeKind::Consumed => "consumed",
+            ChangeKind::Sold => "sold",
+            ChangeKind::Lost => "lost",
+            ChangeKind::Revalued => "revalued",
+        }
+    }
+}
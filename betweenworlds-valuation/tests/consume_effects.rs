@@ -0,0 +1,152 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{ItemValue, ValueSource, Valuator};
+use serde_json::{json, Value};
+
+use common::item;
+
+fn consume_value(consume_effects: Value, quality: u8) -> f64 {
+    let catalog = ItemCatalog::default();
+    Valuator::new(&catalog).consume_value(&item("Test item", 0, consume_effects), quality)
+}
+
+#[test]
+fn no_effects() {
+    assert_eq!(consume_value(Value::Null, 0), 0.0);
+    assert_eq!(consume_value(json!([]), 0), 0.0);
+}
+
+#[test]
+fn unknown() {
+    assert_eq!(consume_value(json!([{"type": 0}]), 0), 0.0);
+}
+
+#[test]
+fn stat_effects_are_worthless() {
+    for tag in 1..=12 {
+        assert_eq!(consume_value(json!([{"type": tag, "min": 10, "max": 20}]), 4), 0.0, "effect {tag}");
+    }
+}
+
+#[test]
+fn add_credits() {
+    assert_eq!(consume_value(json!([{"type": 13, "min": 10, "max": 21}]), 0), 15.5);
+    assert_eq!(consume_value(json!([{"type": 13, "min": 10, "max": 20}]), 4), 15.0);
+}
+
+#[test]
+fn add_credits_worth_multiplier_scales_with_quality() {
+    let effects = json!([{"type": 13, "min": 10, "max": 20, "worthMultiplier": 5}]);
+    assert_eq!(consume_value(effects.clone(), 0), 20.0);
+    assert_eq!(consume_value(effects, 4), 40.0);
+}
+
+#[test]
+fn remove_credits() {
+    assert_eq!(consume_value(json!([{"type": 14, "min": 10, "max": 20}]), 0), -15.0);
+}
+
+#[test]
+fn remove_credits_before_add_credits() {
+    let effects = json!([
+        {"type": 14, "min": 100, "max": 100},
+        {"type": 13, "min": 10, "max": 20}
+    ]);
+    assert_eq!(consume_value(effects.clone(), 0), -85.0);
+    let catalog = ItemCatalog::default();
+    assert_eq!(
+        Valuator::new(&catalog).item_value(&item("Test item", 3, effects), 0),
+        ItemValue { value: 3, source: ValueSource::SellPrice }
+    );
+}
+
+#[test]
+fn accept_mission() {
+    assert_eq!(consume_value(json!([{"type": 15, "missionName": "A mission"}]), 0), 0.0);
+}
+
+fn add_item(item_name: &str, chance: f64, quality: (u8, u8), quantity: (usize, usize)) -> Value {
+    json!({
+        "type": 16,
+        "chance": chance,
+        "itemName": item_name,
+        "qualityMin": quality.0,
+        "qualityMax": quality.1,
+        "quantityMin": quantity.0,
+        "quantityMax": quantity.1
+    })
+}
+
+#[test]
+fn add_item_expected_value() {
+    let catalog = ItemCatalog::new(vec![item("Gem", 10, Value::Null)]);
+    let valuator = Valuator::new(&catalog);
+    // Half the time 1 to 3 gems of quality 1 to 4, rounded to 3, worth 40 each.
+    let loot_box = item("Test item", 0, json!([add_item("gem", 0.5, (1, 4), (1, 3))]));
+    assert_eq!(valuator.consume_value(&loot_box, 0), 40.0);
+    assert_eq!(valuator.item_value(&loot_box, 0), ItemValue { value: 40, source: ValueSource::Consume });
+}
+
+#[test]
+fn add_item_missing_from_the_catalog() {
+    let catalog = ItemCatalog::default();
+    let loot_box = item("Test item", 5, json!([add_item("Gem", 1.0, (0, 0), (1, 1))]));
+    assert_eq!(Valuator::new(&catalog).consume_value(&loot_box, 0), 0.0);
+}
+
+#[test]
+fn add_item_recurses_through_loot() {
+    let catalog = ItemCatalog::new(vec![
+        item("Gem", 10, Value::Null),
+        item("Small box", 1, json!([add_item("Gem", 1.0, (0, 0), (2, 2))]))
+    ]);
+    let big_box = item("Test item", 0, json!([add_item("Small box", 1.0, (0, 0), (3, 3))]));
+    assert_eq!(Valuator::new(&catalog).consume_value(&big_box, 0), 60.0);
+}
+
+#[test]
+fn add_item_cycles_count_the_sell_price() {
+    let catalog = ItemCatalog::new(vec![
+        item("Red box", 2, json!([add_item("Blue box", 1.0, (0, 0), (1, 1))])),
+        item("Blue box", 3, json!([add_item("Red box", 1.0, (0, 0), (10, 10))]))
+    ]);
+    let valuator = Valuator::new(&catalog);
+    let red_box = catalog.get("Red box").unwrap();
+    // The blue box gives back 10 red boxes at their sell price of 2.
+    assert_eq!(valuator.consume_value(red_box, 0), 20.0);
+    assert_eq!(valuator.consume_value(&item("Test item", 0, json!([add_item("Red box", 1.0, (0, 0), (1, 1))])), 0), 20.0);
+}
+
+#[test]
+fn add_item_chance_is_clamped() {
+    let catalog = ItemCatalog::new(vec![item("Gem", 10, Value::Null)]);
+    let valuator = Valuator::new(&catalog);
+    assert_eq!(valuator.consume_value(&item("Test item", 0, json!([add_item("Gem", 2.0, (0, 0), (1, 1))])), 0), 10.0);
+    assert_eq!(valuator.consume_value(&item("Test item", 0, json!([add_item("Gem", -1.0, (0, 0), (1, 1))])), 0), 0.0);
+}
+
+#[test]
+fn unrecognized() {
+    assert_eq!(consume_value(json!([{"type": 99, "amount": 4}]), 0), 0.0);
+}
+
+#[test]
+fn consume_value_wins_when_strictly_greater() {
+    let catalog = ItemCatalog::default();
+    let valuator = Valuator::new(&catalog);
+    let effects = json!([{"type": 13, "min": 10, "max": 11}]);
+    assert_eq!(
+        valuator.item_value(&item("Test item", 10, effects.clone()), 0),
+        ItemValue { value: 11, source: ValueSource::Consume }
+    );
+    assert_eq!(
+        valuator.item_value(&item("Test item", 11, effects), 0),
+        ItemValue { value: 11, source: ValueSource::SellPrice }
+    );
+    let tie = json!([{"type": 13, "min": 10, "max": 10}]);
+    assert_eq!(
+        valuator.item_value(&item("Test item", 10, tie), 0),
+        ItemValue { value: 10, source: ValueSource::SellPrice }
+    );
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{ItemValue, PriceSheet, ValueSource, Valuator};
+use serde_json::{json, Value};
+
+use common::item;
+
+#[test]
+fn quality_prices_win_over_item_prices() {
+    let mut prices = PriceSheet::new();
+    prices.set("Gem", None, 100);
+    prices.set("gem", Some(4), 1000);
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices.get("GEM", 0), Some(100));
+    assert_eq!(prices.get("Gem", 4), Some(1000));
+    assert_eq!(prices.get("Stone", 0), None);
+}
+
+#[test]
+fn overrides_replace_the_computed_value() {
+    let catalog = ItemCatalog::new(vec![item("Gem", 10, Value::Null), item("Stone", 1, Value::Null)]);
+    let mut prices = PriceSheet::new();
+    prices.set("Gem", Some(4), 1000);
+    let valuator = Valuator::new(&catalog).with_prices(&prices);
+    let gem = catalog.get("Gem").unwrap();
+    assert_eq!(valuator.item_value(gem, 4), ItemValue { value: 1000, source: ValueSource::Override });
+    assert_eq!(valuator.item_value(gem, 3), ItemValue { value: 40, source: ValueSource::SellPrice });
+}
+
+#[test]
+fn overrides_apply_to_dropped_items() {
+    let catalog = ItemCatalog::new(vec![item("Gem", 10, Value::Null)]);
+    let mut prices = PriceSheet::new();
+    prices.set("Gem", None, 1000);
+    let loot_box = item("Box", 1, json!([{
+        "type": 16,
+        "chance": 0.5,
+        "itemName": "Gem",
+        "qualityMin": 0,
+        "qualityMax": 0,
+        "quantityMin": 1,
+        "quantityMax": 1
+    }]));
+    assert_eq!(
+        Valuator::new(&catalog).with_prices(&prices).item_value(&loot_box, 0),
+        ItemValue { value: 500, source: ValueSource::Consume }
+    );
+}
+
+#[test]
+fn unknown_items() {
+    let catalog = ItemCatalog::new(vec![item("Gem", 10, Value::Null)]);
+    let mut prices = PriceSheet::new();
+    prices.set("gem", None, 1);
+    prices.set("Stone", None, 1);
+    prices.set("Dragon scale", Some(2), 1);
+    assert_eq!(prices.unknown_items(&catalog), ["Dragon scale", "Stone"]);
+}
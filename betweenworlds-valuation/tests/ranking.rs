@@ -0,0 +1,44 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{NetworthReport, Ranking, Valuator};
+
+use common::user;
+
+fn report(player: &str, credits: usize) -> NetworthReport {
+    let user = user(player, Some(&[]), Some(&[]));
+    Valuator::new(&ItemCatalog::default()).value(&user, Some(credits))
+}
+
+fn ranks(ranking: &Ranking) -> Vec<(usize, &str, usize, i64)> {
+    ranking.entries.iter()
+        .map(|entry| (entry.rank, entry.player.as_str(), entry.total, entry.delta))
+        .collect()
+}
+
+#[test]
+fn ranked_by_total() {
+    let ranking = Ranking::new(&[report("a", 10), report("b", 30), report("c", 20)]);
+    assert_eq!(ranking.median, 20);
+    assert_eq!(ranks(&ranking), [(1, "b", 30, 10), (2, "c", 20, 0), (3, "a", 10, -10)]);
+}
+
+#[test]
+fn even_number_of_players() {
+    let ranking = Ranking::new(&[report("a", 10), report("b", 40), report("c", 20), report("d", 25)]);
+    assert_eq!(ranking.median, 22);
+    assert_eq!(ranks(&ranking)[3], (4, "a", 10, -12));
+}
+
+#[test]
+fn ties_share_their_rank() {
+    let ranking = Ranking::new(&[report("b", 20), report("a", 20), report("c", 10)]);
+    assert_eq!(ranks(&ranking), [(1, "a", 20, 0), (1, "b", 20, 0), (3, "c", 10, -10)]);
+}
+
+#[test]
+fn no_players() {
+    let ranking = Ranking::new(&[]);
+    assert_eq!(ranking.median, 0);
+    assert!(ranking.entries.is_empty());
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{Category, NetworthReport, ReportLine, Valuator};
+use serde_json::Value;
+
+use common::{item, user};
+
+fn catalog() -> ItemCatalog {
+    ItemCatalog::new(vec![
+        item("Sword", 100, Value::Null),
+        item("Axe", 100, Value::Null),
+        item("Helmet", 40, Value::Null),
+        item("Gem", 10, Value::Null)
+    ])
+}
+
+/// A report worth 1000, 280 of items and 720 credits.
+fn report() -> NetworthReport {
+    let user = user("player", Some(&[("Helmet", 0), ("Sword", 0)]), Some(&[("Gem", 0, 4), ("Axe", 0, 1)]));
+    Valuator::new(&catalog()).value(&user, Some(720))
+}
+
+fn lines(lines: &[ReportLine]) -> Vec<(&str, usize)> {
+    lines.iter().map(|line| (line.item_name.as_str(), line.line_total)).collect()
+}
+
+#[test]
+fn most_valuable_first_then_by_name() {
+    let report = report();
+    assert_eq!(lines(&report.lines), [("Axe", 100), ("Sword", 100), ("Gem", 40), ("Helmet", 40)]);
+}
+
+#[test]
+fn shares_of_the_total() {
+    let report = report();
+    assert_eq!(report.total, 1000);
+    let shares: Vec<f64> = report.lines.iter().map(|line| line.share).collect();
+    assert_eq!(shares, [0.1, 0.1, 0.04, 0.04]);
+}
+
+#[test]
+fn shares_of_a_report_worth_nothing() {
+    let catalog = ItemCatalog::new(vec![item("Rag", 0, Value::Null)]);
+    let report = Valuator::new(&catalog).value(&user("player", Some(&[("Rag", 0)]), Some(&[("Rag", 0, 3)])), Some(0));
+    assert_eq!(report.total, 0);
+    assert_eq!(report.lines.len(), 2);
+    assert!(report.lines.iter().all(|line| line.share == 0.0));
+}
+
+#[test]
+fn top_keeps_the_totals() {
+    let report = report();
+    assert_eq!(lines(report.top(2)), [("Axe", 100), ("Sword", 100)]);
+    assert_eq!(report.top(10).len(), 4);
+    assert!(report.top(0).is_empty());
+    assert_eq!((report.equipment, report.inventory, report.total), (Some(140), Some(140), 1000));
+}
+
+#[test]
+fn lines_of_a_category() {
+    let report = report();
+    let equipment: Vec<&str> = report.lines_in(Category::Equipment).map(|line| line.item_name.as_str()).collect();
+    let inventory: Vec<&str> = report.lines_in(Category::Inventory).map(|line| line.item_name.as_str()).collect();
+    assert_eq!(equipment, ["Sword", "Helmet"]);
+    assert_eq!(inventory, ["Axe", "Gem"]);
+}
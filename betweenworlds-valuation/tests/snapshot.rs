@@ -0,0 +1,90 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{ChangeKind, NetworthSnapshot, Valuator};
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+
+use common::{item, user};
+
+fn catalog() -> ItemCatalog {
+    ItemCatalog::new(vec![
+        item("Sword", 100, Value::Null),
+        item("Gem", 10, Value::Null),
+        item("Potion", 5, json!([{"type": 1, "min": 10, "max": 20}])),
+        item("Stone", 1, Value::Null)
+    ])
+}
+
+/// A snapshot of a player with the equipment and the inventory stacks as (name, quality, quantity).
+fn snapshot(equipment: &[(&str, u8)], inventory: &[(&str, u8, usize)], credits: usize, hour: u32) -> NetworthSnapshot {
+    let user = user("player", Some(equipment), Some(inventory));
+    let catalog = catalog();
+    let report = Valuator::new(&catalog).value(&user, Some(credits));
+    NetworthSnapshot::new(report, &catalog, Utc.with_ymd_and_hms(2023, 1, 1, hour, 0, 0).unwrap())
+}
+
+#[test]
+fn gained_consumed_and_sold() {
+    let older = snapshot(&[("Sword", 0)], &[("Potion", 0, 3), ("Stone", 0, 2)], 100, 0);
+    let newer = snapshot(&[("Sword", 0)], &[("Potion", 0, 1), ("Gem", 1, 2)], 150, 1);
+    let diff = older.diff(&newer);
+    let changes: Vec<_> = diff.changes.iter()
+        .map(|change| (change.kind, change.item_name.as_str(), change.quantity_delta(), change.value_delta()))
+        .collect();
+    assert_eq!(changes, [
+        (ChangeKind::Gained, "Gem", 2, 40),
+        (ChangeKind::Consumed, "Potion", -2, -10),
+        (ChangeKind::Sold, "Stone", -2, -2)
+    ]);
+    assert_eq!(diff.credits_delta(), Some(50));
+    assert_eq!(diff.total_delta(), 78);
+}
+
+#[test]
+fn lost_when_the_credits_didnt_rise() {
+    let older = snapshot(&[("Sword", 0)], &[("Potion", 0, 3)], 100, 0);
+    let newer = snapshot(&[], &[("Potion", 0, 2)], 80, 1);
+    let kinds: Vec<_> = older.diff(&newer).changes.iter().map(|change| (change.kind, change.item_name.clone())).collect();
+    assert_eq!(kinds, [(ChangeKind::Lost, "Sword".to_string()), (ChangeKind::Consumed, "Potion".to_string())]);
+}
+
+#[test]
+fn lost_without_credits() {
+    let catalog = catalog();
+    let older = Valuator::new(&catalog).value(&user("player", Some(&[("Sword", 0)]), Some(&[])), None);
+    let older = NetworthSnapshot::new(older, &catalog, Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap());
+    let newer = snapshot(&[], &[], 100, 1);
+    let diff = older.diff(&newer);
+    assert_eq!(diff.changes.iter().map(|change| change.kind).collect::<Vec<_>>(), [ChangeKind::Lost]);
+    assert_eq!(diff.credits_delta(), None);
+}
+
+#[test]
+fn moving_items_changes_nothing() {
+    let older = snapshot(&[("Sword", 2)], &[], 0, 0);
+    let newer = snapshot(&[], &[("sword", 2, 1)], 0, 1);
+    let diff = older.diff(&newer);
+    assert!(diff.changes.is_empty());
+    assert_eq!(diff.total_delta(), 0);
+}
+
+#[test]
+fn qualities_are_compared_apart() {
+    let older = snapshot(&[], &[("Gem", 0, 1)], 0, 0);
+    let newer = snapshot(&[], &[("Gem", 4, 1)], 0, 1);
+    let diff = older.diff(&newer);
+    assert_eq!(diff.changes_of(ChangeKind::Gained).map(|change| change.quality).collect::<Vec<_>>(), [4]);
+    assert_eq!(diff.changes_of(ChangeKind::Lost).map(|change| change.quality).collect::<Vec<_>>(), [0]);
+}
+
+#[test]
+fn round_trips_through_json() {
+    let snapshot = snapshot(&[("Sword", 1)], &[("Gem", 0, 5)], 10, 0);
+    let text = serde_json::to_string(&snapshot).unwrap();
+    let parsed: NetworthSnapshot = serde_json::from_str(&text).unwrap();
+    assert_eq!(parsed.taken_at, snapshot.taken_at);
+    assert_eq!(parsed.report.lines, snapshot.report.lines);
+    assert_eq!(parsed.consumables, snapshot.consumables);
+    assert!(snapshot.diff(&parsed).changes.is_empty());
+}
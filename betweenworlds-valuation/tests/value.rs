@@ -0,0 +1,89 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{Category, UnvaluedLine, Valuator, ValueSource, sell_price};
+use serde_json::Value;
+
+use common::{item, user};
+
+fn catalog() -> ItemCatalog {
+    ItemCatalog::new(vec![
+        item("Sword", 100, Value::Null),
+        item("Helmet", 40, Value::Null),
+        item("Gem", 10, Value::Null)
+    ])
+}
+
+#[test]
+fn sell_price_grows_with_the_quality() {
+    let gem = item("Gem", 10, Value::Null);
+    assert_eq!(sell_price(&gem, 0), 10);
+    assert_eq!(sell_price(&gem, 2), 30);
+    assert_eq!(sell_price(&gem, 4), 50);
+}
+
+#[test]
+fn equipment_inventory_and_credits() {
+    let catalog = catalog();
+    let user = user("player", Some(&[("Sword", 1), ("Helmet", 0)]), Some(&[("Gem", 4, 3), ("Sword", 0, 2)]));
+    let report = Valuator::new(&catalog).value(&user, Some(1000));
+    assert_eq!(report.player, "player");
+    assert_eq!(report.equipment, Some(240));
+    assert_eq!(report.inventory, Some(350));
+    assert_eq!(report.credits, Some(1000));
+    assert_eq!(report.total, 1590);
+    assert!(!report.partial);
+
+    let gems = report.lines_in(Category::Inventory).find(|line| line.item_name == "Gem").unwrap();
+    assert_eq!((gems.unit_value, gems.quantity, gems.line_total), (50, 3, 150));
+    assert_eq!(gems.quality_adjective, "Pristine");
+    assert_eq!(gems.value_source, ValueSource::SellPrice);
+    assert_eq!(report.lines_in(Category::Equipment).count(), 2);
+}
+
+#[test]
+fn without_credits() {
+    let catalog = catalog();
+    let report = Valuator::new(&catalog).value(&user("player", Some(&[("Sword", 0)]), Some(&[])), None);
+    assert_eq!(report.credits, None);
+    assert_eq!(report.inventory, Some(0));
+    assert_eq!(report.total, 100);
+}
+
+#[test]
+fn missing_equipment_or_inventory() {
+    let catalog = catalog();
+    let report = Valuator::new(&catalog).value(&user("player", None, Some(&[("Gem", 0, 1)])), Some(5));
+    assert_eq!(report.equipment, None);
+    assert_eq!(report.inventory, Some(10));
+    assert_eq!(report.total, 15);
+
+    let report = Valuator::new(&catalog).value(&user("player", Some(&[("Gem", 0)]), None), None);
+    assert_eq!(report.equipment, Some(10));
+    assert_eq!(report.inventory, None);
+    assert_eq!(report.total, 10);
+}
+
+#[test]
+fn item_names_ignore_case() {
+    let catalog = catalog();
+    let report = Valuator::new(&catalog).value(&user("player", Some(&[("sWORD", 0)]), None), None);
+    assert_eq!(report.lines[0].item_name, "Sword");
+    assert_eq!(report.total, 100);
+}
+
+#[test]
+fn items_missing_from_the_catalog() {
+    let catalog = catalog();
+    let user = user("player", Some(&[("Sword", 0), ("Crown", 2)]), Some(&[("Gem", 0, 2), ("Relic", 1, 3)]));
+    let report = Valuator::new(&catalog).value(&user, Some(5));
+    assert!(report.partial);
+    assert_eq!(report.unvalued, [
+        UnvaluedLine { item_name: "Crown".to_string(), quality: 2, quantity: 1, category: Category::Equipment },
+        UnvaluedLine { item_name: "Relic".to_string(), quality: 1, quantity: 3, category: Category::Inventory }
+    ]);
+    assert!(report.lines.iter().all(|line| line.item_name != "Crown" && line.item_name != "Relic"));
+    assert_eq!(report.equipment, Some(100));
+    assert_eq!(report.inventory, Some(20));
+    assert_eq!(report.total, 125);
+}
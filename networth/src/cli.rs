@@ -0,0 +1,146 @@
+use std::{collections::HashSet, fs, path::PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::{Failure, output::Format};
+
+/// Calculates the networth of betweenworlds accounts.
+///
+/// The credentials are read from the flags, their environment variables or a profile,
+/// the ones that are still missing are asked for on stdin.
+#[derive(Debug, Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(flatten)]
+    pub credentials: CredentialsArgs,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// The arguments of the `value` command, used when no command is given.
+    #[command(flatten)]
+    pub value: ValueArgs
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Value a player's equipment, inventory and credits (the default command).
+    Value(ValueArgs),
+    /// Save a player's full report with the time it was taken to the snapshot store.
+    Snapshot(SnapshotArgs),
+    /// Compare two snapshots: the items that were gained, consumed, lost or sold and how much they were worth.
+    Diff(DiffArgs)
+}
+
+#[derive(Debug, Args)]
+pub struct CredentialsArgs {
+    /// Your account's username.
+    #[arg(long, env = "BETWEENWORLDS_AUTH_ID", global = true)]
+    pub auth_id: Option<String>,
+    /// An api key, you can generate one in the account settings.
+    #[arg(long, env = "BETWEENWORLDS_API_KEY", hide_env_values = true, global = true)]
+    pub api_key: Option<String>,
+    /// Read the credentials from a profile of the profiles file.
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    /// A TOML file with a table per profile holding its `auth_id` and `api_key`.
+    #[arg(long, env = "BETWEENWORLDS_PROFILES", default_value = "profiles.toml", global = true)]
+    pub profiles: PathBuf
+}
+
+#[derive(Debug, Args)]
+pub struct ValueArgs {
+    /// The player to value, your own account by default.
+    /// Pass it several times to rank the players by their networth.
+    #[arg(long)]
+    pub player: Vec<String>,
+    /// A file with a player name per line to rank by their networth, empty lines and lines starting with `#` are skipped.
+    #[arg(long, value_name = "FILE")]
+    pub players_file: Option<PathBuf>,
+    /// How many players to value at the same time when ranking players.
+    #[arg(long, value_name = "N", default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: u16,
+    /// Write the report to a file instead of stdout.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// How to format the report.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: Format,
+    /// Only list the N most valuable items or the N richest players, the totals and the median still count everything.
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+    /// Leave the credits out of the networth.
+    #[arg(long)]
+    pub no_credits: bool,
+    /// Only value the inventory or the equipment, the credits are left out.
+    #[arg(long, value_enum)]
+    pub only: Option<Only>,
+    /// A TOML or csv file of prices that replace the computed value of items, e.g. their market prices.
+    #[arg(long, value_name = "FILE")]
+    pub prices: Option<PathBuf>
+}
+
+#[derive(Debug, Args)]
+pub struct SnapshotArgs {
+    /// The player to take a snapshot of, your own account by default.
+    #[arg(long)]
+    pub player: Option<String>,
+    /// Leave the credits out of the networth.
+    #[arg(long)]
+    pub no_credits: bool,
+    /// A TOML or csv file of prices that replace the computed value of items, e.g. their market prices.
+    #[arg(long, value_name = "FILE")]
+    pub prices: Option<PathBuf>,
+    #[command(flatten)]
+    pub store: StoreArgs
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// The older snapshot, a path or the name of a file of the snapshot store.
+    pub older: String,
+    /// The newer snapshot, a path or the name of a file of the snapshot store.
+    pub newer: String,
+    /// Write the diff to a file instead of stdout.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// How to format the diff.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: Format,
+    #[command(flatten)]
+    pub store: StoreArgs
+}
+
+#[derive(Debug, Args)]
+pub struct StoreArgs {
+    /// The directory the snapshots are saved to.
+    #[arg(long, env = "BETWEENWORLDS_SNAPSHOTS", default_value = "snapshots")]
+    pub store: PathBuf
+}
+
+impl ValueArgs {
+    /// The players to value: the ones passed with `--player` then the ones of the players file,
+    /// or the account of the credentials if there are none. Repeated names are kept once,
+    /// names that only differ by case are different players.
+    pub fn players(&self, auth_id: &str) -> Result<Vec<String>, Failure> {
+        let mut names = self.player.clone();
+        if let Some(path) = &self.players_file {
+            let text = fs::read_to_string(path)
+                .map_err(|error| Failure::Other(format!("Unable to read the players file {}: {error}.", path.display())))?;
+            names.extend(text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string));
+        }
+        if names.is_empty() {
+            return Ok(vec![auth_id.to_string()]);
+        }
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        Ok(names)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Only {
+    Inventory,
+    Equipment
+}
@@ -0,0 +1,75 @@
+use std::{collections::HashMap, fs, io::{self, Write}, path::Path};
+
+use betweenworlds_api::{ApiError, CredentialsStatus};
+use serde::Deserialize;
+
+use crate::{Failure, cli::CredentialsArgs};
+
+pub struct Credentials {
+    pub auth_id: String,
+    pub api_key: String
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    auth_id: Option<String>,
+    api_key: Option<String>
+}
+
+/// Get the credentials from the flags, then the profile and finally ask for the missing ones on stdin.
+/// The flags already fall back to their environment variables.
+pub fn resolve(args: &CredentialsArgs) -> Result<Credentials, Failure> {
+    resolve_with(args, read_line)
+}
+
+/// Like [`resolve`] but the missing credentials are asked for with `prompt`, it gets the name of the credential.
+pub fn resolve_with<P: FnMut(&str) -> Result<String, Failure>>(args: &CredentialsArgs, mut prompt: P) -> Result<Credentials, Failure> {
+    let profile = match &args.profile {
+        Some(name) => Some(load_profile(&args.profiles, name)?),
+        None => None
+    };
+    let (profile_auth_id, profile_api_key) = match profile {
+        Some(profile) => (profile.auth_id, profile.api_key),
+        None => (None, None)
+    };
+    let auth_id = match args.auth_id.clone().or(profile_auth_id) {
+        Some(auth_id) => auth_id,
+        None => prompt("authId: ")?
+    };
+    let api_key = match args.api_key.clone().or(profile_api_key) {
+        Some(api_key) => api_key,
+        None => prompt("apiKey: ")?
+    };
+    Ok(Credentials { auth_id, api_key })
+}
+
+/// Fail unless the api accepted the credentials, see [`betweenworlds_api::Client::verify_credentials`].
+pub fn verified(status: Result<CredentialsStatus, ApiError>) -> Result<(), Failure> {
+    match status {
+        Ok(CredentialsStatus::Valid) => Ok(()),
+        Ok(CredentialsStatus::Unauthorized) => Err(Failure::Unauthorized),
+        Ok(CredentialsStatus::Unreachable) => Err(Failure::Network),
+        Err(error) => Err(Failure::api(error, "Unable to verify your credentials"))
+    }
+}
+
+fn load_profile(path: &Path, name: &str) -> Result<Profile, Failure> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| Failure::Other(format!("Unable to read the profiles file {}: {error}.", path.display())))?;
+    let mut profiles: HashMap<String, Profile> = toml::from_str(&text)
+        .map_err(|error| Failure::Other(format!("Unable to parse the profiles file {}: {error}", path.display())))?;
+    profiles.remove(name)
+        .ok_or_else(|| Failure::Other(format!("There's no profile named {name} in {}.", path.display())))
+}
+
+fn read_line(text: &str) -> Result<String, Failure> {
+    let read = || -> io::Result<String> {
+        // The prompt goes to stderr so it doesn't end up in the report.
+        eprint!("{text}");
+        io::stderr().flush()?;
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        Ok(line.trim().to_string())
+    };
+    read().map_err(|error| Failure::Other(format!("Unable to read the credentials: {error}.")))
+}
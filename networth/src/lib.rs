@@ -0,0 +1,88 @@
+//! Calculates the networth of betweenworlds accounts, the binary fetches the data and writes the results.
+
+use std::fmt;
+
+use betweenworlds_api::ApiError;
+use betweenworlds_valuation::NetworthReport;
+
+pub mod cli;
+pub mod credentials;
+pub mod output;
+pub mod prices;
+pub mod snapshots;
+
+/// Why the program failed, every kind of failure exits with its own code.
+#[derive(Debug)]
+pub enum Failure {
+    /// The api rejected the credentials.
+    Unauthorized,
+    PlayerNotFound(String),
+    /// The api couldn't be reached or didn't answer in time.
+    Network,
+    /// The report was written but these items aren't in the item catalog so they weren't valued.
+    Partial(Vec<String>),
+    /// The ranking was written without these players because they couldn't be valued.
+    Players(Vec<(String, Failure)>),
+    Other(String)
+}
+
+impl Failure {
+    pub fn api(error: ApiError, context: &str) -> Self {
+        match error {
+            ApiError::Unauthorized => Failure::Unauthorized,
+            ApiError::Unreachable | ApiError::RequestTimeout => Failure::Network,
+            error => Failure::Other(format!("{context}: {error}."))
+        }
+    }
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Failure::Other(_) => 1,
+            Failure::Unauthorized => 3,
+            Failure::PlayerNotFound(_) => 4,
+            Failure::Network => 5,
+            Failure::Partial(_) => 6,
+            // The ranking was still written, which a single player's failure codes would hide.
+            Failure::Players(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Unauthorized => write!(f, "The api rejected your credentials, make sure the authId is your username and the apiKey is up to date."),
+            Failure::PlayerNotFound(name) => write!(f, "There's no player named {name}."),
+            Failure::Network => write!(f, "Unable to reach the betweenworlds api, check your internet connection and try again."),
+            Failure::Partial(names) => write!(
+                f,
+                "Warning: the networth is partial, these items aren't in the item catalog so they weren't valued: {}.",
+                names.join(", ")
+            ),
+            Failure::Players(failures) => {
+                write!(f, "These players were left out of the ranking:")?;
+                for (player, failure) in failures {
+                    write!(f, "\n{player}: {failure}")?;
+                }
+                Ok(())
+            },
+            Failure::Other(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Fail with the items that couldn't be valued if any report is partial.
+pub fn partial(reports: &[NetworthReport]) -> Result<(), Failure> {
+    let mut names: Vec<String> = Vec::new();
+    for line in reports.iter().flat_map(|report| &report.unvalued) {
+        if !names.contains(&line.item_name) {
+            names.push(line.item_name.clone());
+        }
+    }
+    if names.is_empty() {
+        Ok(())
+    }
+    else {
+        Err(Failure::Partial(names))
+    }
+}
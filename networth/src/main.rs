@@ -1,105 +1,180 @@
-use std::io::{self, Write};
-use betweenworlds_api::{Client, UserDataFlags, LeaderboardsFlags, Item, ConsumeEffect};
-use num_format::{Locale, ToFormattedString};
-use std::env;
+use std::{fs, path::Path, process::ExitCode, sync::atomic::{AtomicUsize, Ordering}, thread};
+use betweenworlds_api::{ApiError, Client, ItemCatalog, UserDataFlags, LeaderboardsFlags};
+use betweenworlds_valuation::{NetworthReport, NetworthSnapshot, PriceSheet, Ranking, Valuator};
+use chrono::Utc;
+use clap::Parser;
+use networth::{Failure, credentials, output, partial, prices, snapshots};
+use networth::cli::{Cli, Command, CredentialsArgs, DiffArgs, Only, SnapshotArgs, ValueArgs};
 
-fn main() {
-    println!("Welcome to the betweenworlds networth calculator!");
-    let locale = Locale::en;
-    let username = if let Some(arg) = env::args().nth(1) {
-        arg
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("{failure}");
+            ExitCode::from(failure.exit_code())
+        }
     }
-    else {
-        read_line("username: ").trim().to_string()
-    };
+}
 
-    let api_key = if let Some(arg) = env::args().nth(2) {
-        arg
+fn run(cli: Cli) -> Result<(), Failure> {
+    match cli.command.unwrap_or(Command::Value(cli.value)) {
+        Command::Value(args) => {
+            let (client, auth_id) = connect(&cli.credentials)?;
+            value(&client, &auth_id, &args)
+        },
+        Command::Snapshot(args) => {
+            let (client, auth_id) = connect(&cli.credentials)?;
+            snapshot(&client, &auth_id, &args)
+        },
+        // Snapshots are local, comparing them doesn't need the api.
+        Command::Diff(args) => diff(&args)
     }
-    else {
-        read_line("api-key: ").trim().to_string()
-    };
+}
 
-    let client = Client::new(username.to_string(), api_key.to_string());
-    let user = client.get_user(&username, UserDataFlags::Inventory | UserDataFlags::Equipment).unwrap();
-    let items_collection = client.get_items_map().unwrap();
+/// Resolve and verify the credentials, returns a client and the username of the credentials.
+fn connect(args: &CredentialsArgs) -> Result<(Client, String), Failure> {
+    let credentials = credentials::resolve(args)?;
+    let client = Client::new(credentials.auth_id.clone(), credentials.api_key);
+    credentials::verified(client.verify_credentials())?;
+    Ok((client, credentials.auth_id))
+}
 
-    
-    let mut total = 0;
+fn value(client: &Client, auth_id: &str, args: &ValueArgs) -> Result<(), Failure> {
+    let players = args.players(auth_id)?;
+    let prices = args.prices.as_deref().map(prices::load).transpose()?;
+    let catalog = get_catalog(client)?;
+    let valuator = valuator(&catalog, prices.as_ref());
 
-    match user.equipment {
-        Some(equipment) => {
-            let mut equipment_worth = 0;
-            for item in equipment {
-                let item_info = items_collection.get(&item.item_name).expect("couldn't find item");
-                let sell_price = calculate_item_worth(item_info, item.quality) as usize;
-                equipment_worth += sell_price;
-            }
-            println!("The equipment is worth {} credits.", equipment_worth.to_formatted_string(&locale));
-            total += equipment_worth;
-        },
-        None => {
-            eprintln!("Unable to get equipment");
-        },
+    if let [player] = players.as_slice() {
+        let report = report(client, &valuator, player, args.only, args.no_credits)?;
+        write_output(args.output.as_deref(), output::render(&report, args.format, args.top))?;
+        return partial(&[report]);
     }
 
-    match user.inventory {
-        Some(inventory) => {
-            let mut inventory_worth = 0;
-            for item in inventory {
-                let item_info = items_collection.get(&item.item_name).expect("couldn't find item");
-                let sell_price = calculate_item_worth(item_info, item.quality) * item.quantity;
-                inventory_worth += sell_price;
-            }
-            println!("The inventory is worth {} credits.", inventory_worth.to_formatted_string(&locale));
-            total += inventory_worth;
-        },
-        None => {
-            eprintln!("Unable to get inventory");
-        },
+    let results = reports_of(client, &valuator, &players, args);
+    let mut reports = Vec::new();
+    let mut failures = Vec::new();
+    for (player, result) in players.into_iter().zip(results) {
+        match result {
+            Ok(report) => reports.push(report),
+            Err(failure) => failures.push((player, failure))
+        }
     }
-    
-    let leaderboards_user = client.get_leaderboard_user(&username, LeaderboardsFlags::Credits).unwrap();
-    match leaderboards_user.credits {
-        Some(credits) => {
-            println!("The account has {} raw credits", credits.credits.to_formatted_string(&locale));
-            total += credits.credits;
-        },
-        None => {
-            eprintln!("Unable to get credits");
-        },
+    write_output(args.output.as_deref(), output::render_ranking(&Ranking::new(&reports), args.format, args.top))?;
+    if !failures.is_empty() {
+        return Err(Failure::Players(failures));
     }
+    partial(&reports)
+}
 
-    println!("The account networth is {} credits.", total.to_formatted_string(&locale));
+/// Value the players on up to `--jobs` threads, the results are in the same order as the players.
+fn reports_of(client: &Client, valuator: &Valuator, players: &[String], args: &ValueArgs) -> Vec<Result<NetworthReport, Failure>> {
+    let next = AtomicUsize::new(0);
+    let jobs = usize::min(args.jobs as usize, players.len());
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..jobs)
+            .map(|_| scope.spawn(|| {
+                let mut results = Vec::new();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(player) = players.get(index) else {
+                        break;
+                    };
+                    results.push((index, report(client, valuator, player, args.only, args.no_credits)));
+                }
+                results
+            }))
+            .collect();
+        let mut results: Vec<_> = handles.into_iter()
+            .flat_map(|handle| handle.join().expect("A valuation thread panicked."))
+            .collect();
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    })
 }
 
-fn read_line(text: &str) -> String {
-    print!("{text}");
-    io::stdout().flush().unwrap();
-    let mut text = String::new();
-    io::stdin().read_line(&mut text).unwrap();
-    text
+fn snapshot(client: &Client, auth_id: &str, args: &SnapshotArgs) -> Result<(), Failure> {
+    let player = args.player.as_deref().unwrap_or(auth_id);
+    let prices = args.prices.as_deref().map(prices::load).transpose()?;
+    let catalog = get_catalog(client)?;
+    let report = report(client, &valuator(&catalog, prices.as_ref()), player, None, args.no_credits)?;
+    let snapshot = NetworthSnapshot::new(report, &catalog, Utc::now());
+    let path = snapshots::save(&args.store.store, &snapshot)?;
+    println!("Saved the snapshot of {} to {}.", snapshot.report.player, path.display());
+    partial(&[snapshot.report])
 }
 
+fn diff(args: &DiffArgs) -> Result<(), Failure> {
+    let older = snapshots::load(&args.store.store, &args.older)?;
+    let newer = snapshots::load(&args.store.store, &args.newer)?;
+    snapshots::same_player(&older, &newer)?;
+    if older.taken_at > newer.taken_at {
+        eprintln!("Warning: the older snapshot was taken after the newer one.");
+    }
+    write_output(args.output.as_deref(), output::render_diff(&older.diff(&newer), args.format))
+}
 
-fn calculate_item_worth(item: &Item, quality: u8) -> usize {
-    let sell_value = item.worth_multiplier * (quality + 1) as usize;
-    match &item.consume_effects {
-        Some(effects) => {
-            let mut value = 0;
-            for effect in effects {
-                match effect {
-                    ConsumeEffect::AddCredits(effect) => {
-                        value += ((effect.min + effect.max) / 2) as usize;
-                    },
-                    ConsumeEffect::RemoveCredits(effect) => {
-                        value -= isize::max((effect.min + effect.max) / 2, 0) as usize;
-                    }
-                    _ => {}
-                }
+fn get_catalog(client: &Client) -> Result<ItemCatalog, Failure> {
+    client.get_item_catalog().map_err(|error| Failure::api(error, "Unable to get the items"))
+}
+
+/// A valuator using the price sheet if there is one, the items of the sheet that aren't in the catalog are listed in a warning.
+fn valuator<'a>(catalog: &'a ItemCatalog, prices: Option<&'a PriceSheet>) -> Valuator<'a> {
+    let valuator = Valuator::new(catalog);
+    match prices {
+        Some(prices) => {
+            let unknown = prices.unknown_items(catalog);
+            if !unknown.is_empty() {
+                eprintln!("Warning: these items of the price sheet aren't in the item catalog: {}.", unknown.join(", "));
             }
-            usize::max(value, sell_value)
+            valuator.with_prices(prices)
         },
-        None => sell_value,
+        None => valuator
+    }
+}
+
+/// Fetch a player and their credits and value them.
+fn report(client: &Client, valuator: &Valuator, player: &str, only: Option<Only>, no_credits: bool) -> Result<NetworthReport, Failure> {
+    let data_flags = match only {
+        Some(Only::Inventory) => UserDataFlags::Inventory,
+        Some(Only::Equipment) => UserDataFlags::Equipment,
+        None => UserDataFlags::Inventory | UserDataFlags::Equipment
+    };
+    let user = client.get_user(player, data_flags).map_err(|error| match error {
+        ApiError::NotFound => Failure::PlayerNotFound(player.to_string()),
+        error => Failure::api(error, "Unable to get the player")
+    })?;
+    let credits = if no_credits || only.is_some() {
+        None
     }
-}
\ No newline at end of file
+    else {
+        let leaderboards_user = client.get_leaderboard_user(&user.name, LeaderboardsFlags::Credits)
+            .map_err(|error| Failure::api(error, "Unable to get the credits"))?;
+        if leaderboards_user.credits.is_none() {
+            eprintln!("Unable to get the credits of {}", user.name);
+        }
+        leaderboards_user.credits.map(|credits| credits.credits)
+    };
+
+    let report = valuator.value(&user, credits);
+    if data_flags.has(UserDataFlags::Equipment) && report.equipment.is_none() {
+        eprintln!("Unable to get the equipment of {}", user.name);
+    }
+    if data_flags.has(UserDataFlags::Inventory) && report.inventory.is_none() {
+        eprintln!("Unable to get the inventory of {}", user.name);
+    }
+    Ok(report)
+}
+
+/// Write the report to the output file or stdout.
+fn write_output(output: Option<&Path>, text: String) -> Result<(), Failure> {
+    match output {
+        Some(path) => fs::write(path, text)
+            .map_err(|error| Failure::Other(format!("Unable to write the report to {}: {error}.", path.display()))),
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,538 @@
+use std::fmt::{self, Write};
+
+use betweenworlds_valuation::{NetworthDiff, NetworthReport, Ranking, RankingEntry, ReportLine, UnvaluedLine, ValueSource};
+use clap::ValueEnum;
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+
+/// The version of the json output, it's bumped whenever a field is renamed, removed or changes meaning.
+/// Adding fields doesn't change the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Format {
+    /// A table for reading in the terminal.
+    #[default]
+    Table,
+    /// The whole report as a json object.
+    Json,
+    /// A row per item followed by a row per total.
+    Csv,
+    /// The table as a markdown table.
+    Markdown
+}
+
+/// Format the report, every format contains the item lines, the most valuable first, and the totals.
+/// `top` limits the lines to the most valuable ones.
+pub fn render(report: &NetworthReport, format: Format, top: Option<usize>) -> String {
+    let lines = report.top(top.unwrap_or(usize::MAX));
+    match format {
+        Format::Table => table(report, lines).plain().expect("Couldnt write to string."),
+        Format::Markdown => table(report, lines).markdown().expect("Couldnt write to string."),
+        Format::Json => json(report, lines),
+        Format::Csv => csv(report, lines)
+    }
+}
+
+/// Format a ranking of players, the richest first, with how much they're worth compared to the median.
+/// `top` limits the players to the richest ones.
+pub fn render_ranking(ranking: &Ranking, format: Format, top: Option<usize>) -> String {
+    let entries = &ranking.entries[..usize::min(top.unwrap_or(usize::MAX), ranking.entries.len())];
+    match format {
+        Format::Table => ranking_table(ranking, entries).plain().expect("Couldnt write to string."),
+        Format::Markdown => ranking_table(ranking, entries).markdown().expect("Couldnt write to string."),
+        Format::Json => ranking_json(ranking, entries),
+        Format::Csv => ranking_csv(entries)
+    }
+}
+
+/// Format the differences between two snapshots, the biggest change in value first.
+pub fn render_diff(diff: &NetworthDiff, format: Format) -> String {
+    let title = format!(
+        "{} from {} to {}",
+        diff.player,
+        diff.from.format("%Y-%m-%d %H:%M UTC"),
+        diff.to.format("%Y-%m-%d %H:%M UTC")
+    );
+    match format {
+        Format::Table => format!("{title}\n\n{}", diff_table(diff).plain().expect("Couldnt write to string.")),
+        Format::Markdown => format!("{title}\n\n{}", diff_table(diff).markdown().expect("Couldnt write to string.")),
+        Format::Json => diff_json(diff),
+        Format::Csv => diff_csv(diff)
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    schema_version: u32,
+    player: &'a str,
+    lines: &'a [ReportLine],
+    equipment: Option<usize>,
+    inventory: Option<usize>,
+    credits: Option<usize>,
+    total: usize,
+    unvalued: &'a [UnvaluedLine],
+    partial: bool
+}
+
+fn json(report: &NetworthReport, lines: &[ReportLine]) -> String {
+    let report = JsonReport {
+        schema_version: SCHEMA_VERSION,
+        player: &report.player,
+        lines,
+        equipment: report.equipment,
+        inventory: report.inventory,
+        credits: report.credits,
+        total: report.total,
+        unvalued: &report.unvalued,
+        partial: report.partial
+    };
+    let mut text = serde_json::to_string_pretty(&report).expect("Unable to serialize the report.");
+    text.push('\n');
+    text
+}
+
+fn csv(report: &NetworthReport, lines: &[ReportLine]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = || -> csv::Result<()> {
+        writer.write_record(["category", "item_name", "quality", "quantity", "unit_value", "line_total", "quality_adjective", "share", "value_source"])?;
+        for line in lines {
+            writer.write_record([
+                line.category.name(),
+                &line.item_name,
+                &line.quality.to_string(),
+                &line.quantity.to_string(),
+                &line.unit_value.to_string(),
+                &line.line_total.to_string(),
+                &line.quality_adjective,
+                &line.share.to_string(),
+                line.value_source.name()
+            ])?;
+        }
+        // The unvalued items have no values.
+        for line in &report.unvalued {
+            writer.write_record([
+                line.category.name(),
+                &line.item_name,
+                &line.quality.to_string(),
+                &line.quantity.to_string(),
+                "",
+                "",
+                "",
+                "",
+                ""
+            ])?;
+        }
+        // The totals are rows of the `total` category named after what they add up.
+        for (name, total) in totals(report) {
+            writer.write_record(["total", name, "", "", "", &total.to_string(), "", "", ""])?;
+        }
+        writer.flush()?;
+        Ok(())
+    };
+    write().expect("Couldnt write the csv.");
+    let bytes = writer.into_inner().expect("Couldnt write the csv.");
+    String::from_utf8(bytes).expect("The csv isn't valid utf-8.")
+}
+
+/// The available totals by name, the networth last.
+fn totals(report: &NetworthReport) -> Vec<(&'static str, usize)> {
+    let mut totals = Vec::new();
+    if let Some(equipment) = report.equipment {
+        totals.push(("equipment", equipment));
+    }
+    if let Some(inventory) = report.inventory {
+        totals.push(("inventory", inventory));
+    }
+    if let Some(credits) = report.credits {
+        totals.push(("credits", credits));
+    }
+    totals.push(("networth", report.total));
+    totals
+}
+
+fn table(report: &NetworthReport, lines: &[ReportLine]) -> Table {
+    let locale = Locale::en;
+    let mut table = Table::new(
+        ["Category", "Item", "Quality", "Quantity", "Unit value", "Total", "Share", "Source"],
+        [false, false, false, true, true, true, true, false]
+    );
+    for line in lines {
+        table.rows.push(vec![
+            line.category.name().to_string(),
+            line.item_name.clone(),
+            format!("{} ({})", line.quality_adjective, line.quality),
+            line.quantity.to_formatted_string(&locale),
+            line.unit_value.to_formatted_string(&locale),
+            line.line_total.to_formatted_string(&locale),
+            format!("{:.1}%", line.share * 100.0),
+            source_label(line.value_source).to_string()
+        ]);
+    }
+    let hidden = report.lines.len() - lines.len();
+    if hidden > 0 {
+        let hidden_total: usize = report.lines[lines.len()..].iter().map(|line| line.line_total).sum();
+        let hidden_share: f64 = report.lines[lines.len()..].iter().map(|line| line.share).sum();
+        table.rows.push(vec![
+            String::new(),
+            format!("{hidden} other items"),
+            String::new(),
+            String::new(),
+            String::new(),
+            hidden_total.to_formatted_string(&locale),
+            format!("{:.1}%", hidden_share * 100.0),
+            String::new()
+        ]);
+    }
+    for (name, total) in totals(report) {
+        let label = match name {
+            "equipment" => "Equipment total",
+            "inventory" => "Inventory total",
+            "credits" => "Credits",
+            _ if report.partial => "Networth (partial)",
+            _ => "Networth"
+        };
+        table.footer.push(vec![label.to_string(), String::new(), String::new(), String::new(), String::new(), total.to_formatted_string(&locale), String::new(), String::new()]);
+    }
+    for line in &report.unvalued {
+        table.unvalued.push(vec![
+            line.category.name().to_string(),
+            line.item_name.clone(),
+            line.quality.to_string(),
+            line.quantity.to_formatted_string(&locale),
+            "unvalued".to_string(),
+            String::new(),
+            String::new(),
+            String::new()
+        ]);
+    }
+    table
+}
+
+/// How the table shows where a value comes from.
+fn source_label(source: ValueSource) -> &'static str {
+    match source {
+        ValueSource::SellPrice => "sell price",
+        ValueSource::Consume => "consume",
+        ValueSource::Override => "override"
+    }
+}
+
+#[derive(Serialize)]
+struct JsonRanking<'a> {
+    schema_version: u32,
+    median: usize,
+    players: &'a [RankingEntry]
+}
+
+fn ranking_json(ranking: &Ranking, entries: &[RankingEntry]) -> String {
+    let ranking = JsonRanking { schema_version: SCHEMA_VERSION, median: ranking.median, players: entries };
+    let mut text = serde_json::to_string_pretty(&ranking).expect("Unable to serialize the ranking.");
+    text.push('\n');
+    text
+}
+
+fn ranking_csv(entries: &[RankingEntry]) -> String {
+    let optional = |value: Option<usize>| value.map(|value| value.to_string()).unwrap_or_default();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = || -> csv::Result<()> {
+        writer.write_record(["rank", "player", "equipment", "inventory", "credits", "total", "delta", "partial"])?;
+        for entry in entries {
+            writer.write_record([
+                &entry.rank.to_string(),
+                &entry.player,
+                &optional(entry.equipment),
+                &optional(entry.inventory),
+                &optional(entry.credits),
+                &entry.total.to_string(),
+                &entry.delta.to_string(),
+                &entry.partial.to_string()
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    };
+    write().expect("Couldnt write the csv.");
+    let bytes = writer.into_inner().expect("Couldnt write the csv.");
+    String::from_utf8(bytes).expect("The csv isn't valid utf-8.")
+}
+
+fn ranking_table(ranking: &Ranking, entries: &[RankingEntry]) -> Table {
+    let locale = Locale::en;
+    let optional = |value: Option<usize>| value.map(|value| value.to_formatted_string(&locale)).unwrap_or_else(|| "-".to_string());
+    let mut table = Table::new(
+        ["Rank", "Player", "Equipment", "Inventory", "Credits", "Total", "vs median"],
+        [true, false, true, true, true, true, true]
+    );
+    for entry in entries {
+        let player = if entry.partial {
+            format!("{} (partial)", entry.player)
+        }
+        else {
+            entry.player.clone()
+        };
+        table.rows.push(vec![
+            entry.rank.to_string(),
+            player,
+            optional(entry.equipment),
+            optional(entry.inventory),
+            optional(entry.credits),
+            entry.total.to_formatted_string(&locale),
+            signed(entry.delta)
+        ]);
+    }
+    let hidden = ranking.entries.len() - entries.len();
+    if hidden > 0 {
+        table.rows.push(vec![
+            String::new(),
+            format!("{hidden} other players"),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new()
+        ]);
+    }
+    table.footer.push(vec![
+        String::new(),
+        "Median".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        ranking.median.to_formatted_string(&locale),
+        String::new()
+    ]);
+    table
+}
+
+#[derive(Serialize)]
+struct JsonDiff<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    diff: &'a NetworthDiff,
+    credits_delta: Option<i64>,
+    total_delta: i64
+}
+
+fn diff_json(diff: &NetworthDiff) -> String {
+    let json = JsonDiff {
+        schema_version: SCHEMA_VERSION,
+        diff,
+        credits_delta: diff.credits_delta(),
+        total_delta: diff.total_delta()
+    };
+    let mut text = serde_json::to_string_pretty(&json).expect("Unable to serialize the diff.");
+    text.push('\n');
+    text
+}
+
+fn diff_csv(diff: &NetworthDiff) -> String {
+    let optional = |value: Option<usize>| value.map(|value| value.to_string()).unwrap_or_default();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = || -> csv::Result<()> {
+        writer.write_record(["change", "item_name", "quality", "old_quantity", "new_quantity", "old_value", "new_value", "value_delta"])?;
+        for change in &diff.changes {
+            writer.write_record([
+                change.kind.name(),
+                &change.item_name,
+                &change.quality.to_string(),
+                &change.old_quantity.to_string(),
+                &change.new_quantity.to_string(),
+                &change.old_value.to_string(),
+                &change.new_value.to_string(),
+                &change.value_delta().to_string()
+            ])?;
+        }
+        // Like in the report the totals are rows of the `total` category.
+        let credits_delta = diff.credits_delta().map(|delta| delta.to_string()).unwrap_or_default();
+        writer.write_record(["total", "credits", "", "", "", &optional(diff.old_credits), &optional(diff.new_credits), &credits_delta])?;
+        writer.write_record([
+            "total",
+            "networth",
+            "",
+            "",
+            "",
+            &diff.old_total.to_string(),
+            &diff.new_total.to_string(),
+            &diff.total_delta().to_string()
+        ])?;
+        writer.flush()?;
+        Ok(())
+    };
+    write().expect("Couldnt write the csv.");
+    let bytes = writer.into_inner().expect("Couldnt write the csv.");
+    String::from_utf8(bytes).expect("The csv isn't valid utf-8.")
+}
+
+fn diff_table(diff: &NetworthDiff) -> Table {
+    let locale = Locale::en;
+    let optional = |value: Option<usize>| value.map(|value| value.to_formatted_string(&locale)).unwrap_or_else(|| "-".to_string());
+    let mut table = Table::new(
+        ["Change", "Item", "Quality", "Before", "After", "Value change"],
+        [false, false, true, true, true, true]
+    );
+    for change in &diff.changes {
+        table.rows.push(vec![
+            change.kind.name().to_string(),
+            change.item_name.clone(),
+            change.quality.to_string(),
+            change.old_quantity.to_formatted_string(&locale),
+            change.new_quantity.to_formatted_string(&locale),
+            signed(change.value_delta())
+        ]);
+    }
+    table.footer.push(vec![
+        "Credits".to_string(),
+        String::new(),
+        String::new(),
+        optional(diff.old_credits),
+        optional(diff.new_credits),
+        diff.credits_delta().map(signed).unwrap_or_else(|| "-".to_string())
+    ]);
+    table.footer.push(vec![
+        "Networth".to_string(),
+        String::new(),
+        String::new(),
+        diff.old_total.to_formatted_string(&locale),
+        diff.new_total.to_formatted_string(&locale),
+        signed(diff.total_delta())
+    ]);
+    table
+}
+
+/// A number with its sign, `+` for positive numbers, and thousands separators.
+fn signed(value: i64) -> String {
+    if value > 0 {
+        format!("+{}", value.to_formatted_string(&Locale::en))
+    }
+    else {
+        value.to_formatted_string(&Locale::en)
+    }
+}
+
+/// Rows of text cells laid out in aligned columns.
+struct Table {
+    headers: Vec<String>,
+    /// Whether each column is aligned to the right.
+    right_aligned: Vec<bool>,
+    rows: Vec<Vec<String>>,
+    /// Rows shown under the others, separated from them.
+    footer: Vec<Vec<String>>,
+    /// Rows of the items that couldn't be valued, shown apart after the table.
+    unvalued: Vec<Vec<String>>
+}
+
+impl Table {
+    fn new<const N: usize>(headers: [&str; N], right_aligned: [bool; N]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            right_aligned: right_aligned.to_vec(),
+            rows: Vec::new(),
+            footer: Vec::new(),
+            unvalued: Vec::new()
+        }
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.chars().count()).collect();
+        for row in self.rows.iter().chain(&self.footer).chain(&self.unvalued) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = usize::max(*width, cell.chars().count());
+            }
+        }
+        widths
+    }
+
+    /// The row's cells padded to the columns' widths.
+    fn cells(&self, row: &[String], widths: &[usize]) -> Vec<String> {
+        row.iter()
+            .zip(widths)
+            .zip(&self.right_aligned)
+            .map(|((cell, width), right_aligned)| {
+                if *right_aligned {
+                    format!("{cell:>width$}")
+                }
+                else {
+                    format!("{cell:<width$}")
+                }
+            })
+            .collect()
+    }
+
+    fn write_row(&self, text: &mut String, row: &[String], widths: &[usize]) -> fmt::Result {
+        writeln!(text, "{}", self.cells(row, widths).join("  ").trim_end())
+    }
+
+    fn plain(&self) -> Result<String, fmt::Error> {
+        let widths = self.widths();
+        let rule = widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("  ");
+        let mut text = String::new();
+        self.write_row(&mut text, &self.headers, &widths)?;
+        writeln!(text, "{rule}")?;
+        for row in &self.rows {
+            self.write_row(&mut text, row, &widths)?;
+        }
+        writeln!(text, "{rule}")?;
+        for row in &self.footer {
+            self.write_row(&mut text, row, &widths)?;
+        }
+        if !self.unvalued.is_empty() {
+            writeln!(text)?;
+            writeln!(text, "Unvalued items, they aren't in the item catalog:")?;
+            for row in &self.unvalued {
+                self.write_row(&mut text, row, &widths)?;
+            }
+        }
+        Ok(text)
+    }
+
+    /// The table with the pipes of its cells escaped for markdown.
+    fn escaped(&self) -> Table {
+        let escape = |row: &Vec<String>| row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+        Table {
+            headers: escape(&self.headers),
+            right_aligned: self.right_aligned.clone(),
+            rows: self.rows.iter().map(escape).collect(),
+            footer: self.footer.iter().map(escape).collect(),
+            unvalued: self.unvalued.iter().map(escape).collect()
+        }
+    }
+
+    fn markdown(&self) -> Result<String, fmt::Error> {
+        // The columns are as wide as the escaped cells so the pipes stay aligned.
+        let table = self.escaped();
+        let widths = table.widths();
+        let mut text = String::new();
+        let write_row = |text: &mut String, row: &[String]| -> fmt::Result {
+            writeln!(text, "| {} |", table.cells(row, &widths).join(" | "))
+        };
+        write_row(&mut text, &table.headers)?;
+        let rule: Vec<String> = widths.iter()
+            .zip(&table.right_aligned)
+            .map(|(width, right_aligned)| {
+                let dashes = "-".repeat(usize::max(*width, 3) - 1);
+                if *right_aligned {
+                    format!("{dashes}:")
+                }
+                else {
+                    format!("{dashes}-")
+                }
+            })
+            .collect();
+        writeln!(text, "| {} |", rule.join(" | "))?;
+        for row in table.rows.iter().chain(&table.footer) {
+            write_row(&mut text, row)?;
+        }
+        if !table.unvalued.is_empty() {
+            writeln!(text)?;
+            writeln!(text, "Unvalued items, they aren't in the item catalog:")?;
+            writeln!(text)?;
+            write_row(&mut text, &table.headers)?;
+            writeln!(text, "| {} |", rule.join(" | "))?;
+            for row in &table.unvalued {
+                write_row(&mut text, row)?;
+            }
+        }
+        Ok(text)
+    }
+}
@@ -0,0 +1,69 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use betweenworlds_valuation::PriceSheet;
+use serde::Deserialize;
+
+use crate::Failure;
+
+/// The highest quality an item can have.
+const MAX_QUALITY: u8 = 4;
+
+/// A price of the TOML sheet, for every quality or by quality.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TomlPrice {
+    All(usize),
+    ByQuality(HashMap<String, usize>)
+}
+
+/// A row of the csv sheet, an empty quality is for every quality.
+#[derive(Deserialize)]
+struct CsvPrice {
+    item_name: String,
+    quality: Option<u8>,
+    price: usize
+}
+
+/// Read a price sheet, a csv file if its extension is `csv` and a TOML file otherwise.
+pub fn load(path: &Path) -> Result<PriceSheet, Failure> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| Failure::Other(format!("Unable to read the price sheet {}: {error}.", path.display())))?;
+    let is_csv = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+    let sheet = if is_csv {
+        parse_csv(&text)
+    }
+    else {
+        parse_toml(&text)
+    };
+    sheet.map_err(|error| Failure::Other(format!("Unable to parse the price sheet {}: {error}", path.display())))
+}
+
+fn parse_toml(text: &str) -> Result<PriceSheet, String> {
+    let prices: HashMap<String, TomlPrice> = toml::from_str(text).map_err(|error| error.to_string())?;
+    let mut sheet = PriceSheet::new();
+    for (item_name, price) in prices {
+        match price {
+            TomlPrice::All(price) => sheet.set(&item_name, None, price),
+            TomlPrice::ByQuality(prices) => for (quality, price) in prices {
+                let quality = quality.parse().ok()
+                    .filter(|quality| *quality <= MAX_QUALITY)
+                    .ok_or_else(|| format!("{item_name} has a price for the quality {quality}, qualities go from 0 to {MAX_QUALITY}."))?;
+                sheet.set(&item_name, Some(quality), price);
+            }
+        }
+    }
+    Ok(sheet)
+}
+
+fn parse_csv(text: &str) -> Result<PriceSheet, String> {
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let mut sheet = PriceSheet::new();
+    for row in reader.deserialize() {
+        let row: CsvPrice = row.map_err(|error| error.to_string())?;
+        if let Some(quality) = row.quality.filter(|quality| *quality > MAX_QUALITY) {
+            return Err(format!("{} has a price for the quality {quality}, qualities go from 0 to {MAX_QUALITY}.", row.item_name));
+        }
+        sheet.set(&row.item_name, row.quality, row.price);
+    }
+    Ok(sheet)
+}
@@ -0,0 +1,61 @@
+use std::{fs::{self, OpenOptions}, io::{self, Write}, path::{Path, PathBuf}};
+
+use betweenworlds_valuation::NetworthSnapshot;
+
+use crate::Failure;
+
+/// Save a snapshot to the store as `<player>-<time>.json`, the store is created if it doesn't exist.
+/// Existing snapshots are never overwritten, another snapshot of the player taken in the same second
+/// is saved as `<player>-<time>-2.json`, then `-3` and so on.
+pub fn save(store: &Path, snapshot: &NetworthSnapshot) -> Result<PathBuf, Failure> {
+    fs::create_dir_all(store)
+        .map_err(|error| Failure::Other(format!("Unable to create the snapshot store {}: {error}.", store.display())))?;
+    let stem = format!("{}-{}", snapshot.report.player, snapshot.taken_at.format("%Y%m%dT%H%M%SZ"));
+    let text = serde_json::to_string_pretty(snapshot).expect("Unable to serialize the snapshot.");
+    let mut path = store.join(format!("{stem}.json"));
+    let mut copy = 1;
+    let mut file = loop {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => break file,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                copy += 1;
+                path = store.join(format!("{stem}-{copy}.json"));
+            },
+            Err(error) => return Err(Failure::Other(format!("Unable to create the snapshot {}: {error}.", path.display())))
+        }
+    };
+    file.write_all(text.as_bytes())
+        .map_err(|error| Failure::Other(format!("Unable to write the snapshot to {}: {error}.", path.display())))?;
+    Ok(path)
+}
+
+/// Read a snapshot from a path or, if there's no such file, from the file of the store with that name,
+/// the `.json` extension can be left out.
+pub fn load(store: &Path, name: &str) -> Result<NetworthSnapshot, Failure> {
+    let path = resolve(store, name)
+        .ok_or_else(|| Failure::Other(format!("There's no snapshot {name} here or in the snapshot store {}.", store.display())))?;
+    let text = fs::read_to_string(&path)
+        .map_err(|error| Failure::Other(format!("Unable to read the snapshot {}: {error}.", path.display())))?;
+    serde_json::from_str(&text)
+        .map_err(|error| Failure::Other(format!("Unable to parse the snapshot {}: {error}.", path.display())))
+}
+
+/// Fail unless both snapshots are of the same player, names that only differ by case are different players.
+pub fn same_player(older: &NetworthSnapshot, newer: &NetworthSnapshot) -> Result<(), Failure> {
+    if older.report.player == newer.report.player {
+        Ok(())
+    }
+    else {
+        Err(Failure::Other(format!(
+            "The snapshots are of different players, {} and {}.",
+            older.report.player,
+            newer.report.player
+        )))
+    }
+}
+
+fn resolve(store: &Path, name: &str) -> Option<PathBuf> {
+    [PathBuf::from(name), store.join(name), store.join(format!("{name}.json"))]
+        .into_iter()
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,8 @@
+//! The item and user fixtures of the api's tests.
+// Every test file only uses some of the fixtures.
+#![allow(dead_code, unused_imports)]
+
+#[path = "../../../betweenworlds-api/tests/common/mod.rs"]
+mod fixtures;
+
+pub use fixtures::*;
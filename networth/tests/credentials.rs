@@ -0,0 +1,103 @@
+use std::{fs, path::PathBuf};
+
+use betweenworlds_api::{ApiError, CredentialsStatus};
+use networth::{Failure, cli::CredentialsArgs, credentials::{resolve_with, verified}};
+
+/// Write a profiles file to a temporary file unique to the test.
+fn profiles(test: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("networth-{test}-{}.toml", std::process::id()));
+    fs::write(&path, text).unwrap();
+    path
+}
+
+const PROFILES: &str = r#"
+[main]
+auth_id = "profile id"
+api_key = "profile key"
+
+[half]
+auth_id = "half id"
+"#;
+
+fn args(auth_id: Option<&str>, api_key: Option<&str>, profile: Option<&str>, profiles: PathBuf) -> CredentialsArgs {
+    CredentialsArgs {
+        auth_id: auth_id.map(str::to_string),
+        api_key: api_key.map(str::to_string),
+        profile: profile.map(str::to_string),
+        profiles
+    }
+}
+
+fn no_prompt(name: &str) -> Result<String, Failure> {
+    panic!("Asked for {name} although every credential was given.")
+}
+
+#[test]
+fn flags_win_over_the_profile() {
+    let path = profiles("flags", PROFILES);
+    let credentials = resolve_with(&args(Some("flag id"), Some("flag key"), Some("main"), path), no_prompt).unwrap();
+    assert_eq!(credentials.auth_id, "flag id");
+    assert_eq!(credentials.api_key, "flag key");
+}
+
+#[test]
+fn profile_fills_the_missing_flags() {
+    let path = profiles("fills", PROFILES);
+    let credentials = resolve_with(&args(Some("flag id"), None, Some("main"), path), no_prompt).unwrap();
+    assert_eq!(credentials.auth_id, "flag id");
+    assert_eq!(credentials.api_key, "profile key");
+}
+
+#[test]
+fn prompt_for_the_rest() {
+    let path = profiles("prompt", PROFILES);
+    let mut prompts = Vec::new();
+    let credentials = resolve_with(&args(None, None, Some("half"), path), |name| {
+        prompts.push(name.to_string());
+        Ok("typed key".to_string())
+    }).unwrap();
+    assert_eq!(credentials.auth_id, "half id");
+    assert_eq!(credentials.api_key, "typed key");
+    assert_eq!(prompts, ["apiKey: "]);
+}
+
+#[test]
+fn prompt_for_everything_without_a_profile() {
+    // The profiles file isn't read when no profile is asked for.
+    let path = std::env::temp_dir().join("networth-missing-profiles.toml");
+    let mut prompts = Vec::new();
+    let credentials = resolve_with(&args(None, None, None, path), |name| {
+        prompts.push(name.to_string());
+        Ok(name.trim_end_matches(": ").to_string())
+    }).unwrap();
+    assert_eq!((credentials.auth_id.as_str(), credentials.api_key.as_str()), ("authId", "apiKey"));
+    assert_eq!(prompts, ["authId: ", "apiKey: "]);
+}
+
+#[test]
+fn unknown_profile() {
+    let path = profiles("unknown", PROFILES);
+    let failure = resolve_with(&args(None, None, Some("other"), path.clone()), no_prompt).map(|_| ()).unwrap_err();
+    match failure {
+        Failure::Other(message) => assert_eq!(message, format!("There's no profile named other in {}.", path.display())),
+        failure => panic!("Unexpected failure {failure:?}")
+    }
+}
+
+#[test]
+fn missing_profiles_file() {
+    let path = std::env::temp_dir().join("networth-no-such-profiles.toml");
+    let failure = resolve_with(&args(None, None, Some("main"), path), no_prompt).map(|_| ()).unwrap_err();
+    assert!(matches!(&failure, Failure::Other(message) if message.starts_with("Unable to read the profiles file")), "{failure:?}");
+    assert_eq!(failure.exit_code(), 1);
+}
+
+#[test]
+fn verification_exit_codes() {
+    let code = |status| verified(status).map_err(|failure| failure.exit_code());
+    assert_eq!(code(Ok(CredentialsStatus::Valid)), Ok(()));
+    assert_eq!(code(Ok(CredentialsStatus::Unauthorized)), Err(3));
+    assert_eq!(code(Ok(CredentialsStatus::Unreachable)), Err(5));
+    assert_eq!(code(Err(ApiError::RequestTimeout)), Err(5));
+    assert_eq!(code(Err(ApiError::Other)), Err(1));
+}
@@ -0,0 +1,28 @@
+//! The environment is shared by the whole process so these checks live in their own test binary.
+
+use clap::Parser;
+use networth::cli::Cli;
+
+#[test]
+fn environment_variables_are_used_unless_a_flag_is_given() {
+    std::env::set_var("BETWEENWORLDS_AUTH_ID", "env id");
+    std::env::set_var("BETWEENWORLDS_API_KEY", "env key");
+    std::env::set_var("BETWEENWORLDS_PROFILES", "env-profiles.toml");
+
+    let cli = Cli::try_parse_from(["networth"]).unwrap();
+    assert_eq!(cli.credentials.auth_id.as_deref(), Some("env id"));
+    assert_eq!(cli.credentials.api_key.as_deref(), Some("env key"));
+    assert_eq!(cli.credentials.profiles.to_str(), Some("env-profiles.toml"));
+
+    let cli = Cli::try_parse_from(["networth", "--auth-id", "flag id", "--profiles", "flag.toml"]).unwrap();
+    assert_eq!(cli.credentials.auth_id.as_deref(), Some("flag id"));
+    assert_eq!(cli.credentials.api_key.as_deref(), Some("env key"));
+    assert_eq!(cli.credentials.profiles.to_str(), Some("flag.toml"));
+
+    std::env::remove_var("BETWEENWORLDS_AUTH_ID");
+    std::env::remove_var("BETWEENWORLDS_API_KEY");
+    std::env::remove_var("BETWEENWORLDS_PROFILES");
+    let cli = Cli::try_parse_from(["networth"]).unwrap();
+    assert_eq!(cli.credentials.auth_id, None);
+    assert_eq!(cli.credentials.profiles.to_str(), Some("profiles.toml"));
+}
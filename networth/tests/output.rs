@@ -0,0 +1,60 @@
+mod common;
+
+use std::{env, fs, path::PathBuf};
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{NetworthReport, Valuator};
+use networth::output::{render, Format};
+use serde_json::{json, Value};
+
+use common::{item, user};
+
+/// A report with equipment, an inventory stack with a pipe in its name, an unvalued item and credits.
+fn report() -> NetworthReport {
+    let catalog = ItemCatalog::new(vec![
+        item("Sword", 100, Value::Null),
+        item("Ink | Quill", 3, Value::Null),
+        item("Potion", 5, json!([{"type": 1, "min": 10, "max": 20}]))
+    ]);
+    let user = user(
+        "player",
+        Some(&[("Sword", 2)]),
+        Some(&[("Ink | Quill", 1, 4), ("Potion", 0, 3), ("Relic", 4, 1)])
+    );
+    Valuator::new(&catalog).value(&user, Some(1234))
+}
+
+/// Compare the output to `tests/golden/<name>`, `UPDATE_GOLDEN=1` rewrites the file instead.
+fn assert_golden(name: &str, output: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, output).unwrap();
+    }
+    let expected = fs::read_to_string(&path).unwrap();
+    assert_eq!(output, expected, "The output differs from {}.", path.display());
+}
+
+#[test]
+fn table() {
+    assert_golden("report.txt", &render(&report(), Format::Table, None));
+}
+
+#[test]
+fn markdown() {
+    assert_golden("report.md", &render(&report(), Format::Markdown, None));
+}
+
+#[test]
+fn json() {
+    assert_golden("report.json", &render(&report(), Format::Json, None));
+}
+
+#[test]
+fn csv() {
+    assert_golden("report.csv", &render(&report(), Format::Csv, None));
+}
+
+#[test]
+fn top_lines() {
+    assert_golden("report-top.txt", &render(&report(), Format::Table, Some(1)));
+}
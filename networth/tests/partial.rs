@@ -0,0 +1,39 @@
+mod common;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{NetworthReport, Valuator};
+use networth::{Failure, partial};
+use serde_json::Value;
+
+use common::{item, user};
+
+/// The report of a player with these inventory items, one of each, only the sword is in the catalog.
+fn report(player: &str, items: &[&str]) -> NetworthReport {
+    let catalog = ItemCatalog::new(vec![item("Sword", 100, Value::Null)]);
+    let inventory: Vec<(&str, u8, usize)> = items.iter().map(|name| (*name, 0, 1)).collect();
+    Valuator::new(&catalog).value(&user(player, Some(&[]), Some(&inventory)), None)
+}
+
+#[test]
+fn complete_reports() {
+    assert!(partial(&[report("a", &["Sword"]), report("b", &[])]).is_ok());
+    assert!(partial(&[]).is_ok());
+}
+
+#[test]
+fn unvalued_items_of_every_report_once() {
+    let failure = partial(&[report("a", &["Relic", "Sword", "Crown"]), report("b", &["Crown", "Gem"])]).unwrap_err();
+    assert!(matches!(&failure, Failure::Partial(names) if names == &["Relic", "Crown", "Gem"]));
+    assert_eq!(failure.exit_code(), 6);
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(Failure::Other(String::new()).exit_code(), 1);
+    assert_eq!(Failure::Unauthorized.exit_code(), 3);
+    assert_eq!(Failure::PlayerNotFound("a".to_string()).exit_code(), 4);
+    assert_eq!(Failure::Network.exit_code(), 5);
+    assert_eq!(Failure::Partial(vec!["Relic".to_string()]).exit_code(), 6);
+    assert_eq!(Failure::Players(vec![("a".to_string(), Failure::PlayerNotFound("a".to_string()))]).exit_code(), 7);
+    assert_eq!(Failure::Players(vec![("a".to_string(), Failure::Network), ("b".to_string(), Failure::Network)]).exit_code(), 7);
+}
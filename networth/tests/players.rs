@@ -0,0 +1,39 @@
+use std::fs;
+
+use clap::Parser;
+use networth::{Failure, cli::Cli};
+
+fn players(arguments: &[&str]) -> Result<Vec<String>, Failure> {
+    let cli = Cli::try_parse_from(["networth"].iter().chain(arguments)).unwrap();
+    cli.value.players("me")
+}
+
+#[test]
+fn own_account_by_default() {
+    assert_eq!(players(&[]).unwrap(), ["me"]);
+}
+
+#[test]
+fn repeated_players_are_kept_once() {
+    assert_eq!(players(&["--player", "Bob", "--player", "alice", "--player", "Bob"]).unwrap(), ["Bob", "alice"]);
+}
+
+#[test]
+fn names_that_differ_by_case_are_different_players() {
+    assert_eq!(players(&["--player", "Foo", "--player", "foo"]).unwrap(), ["Foo", "foo"]);
+}
+
+#[test]
+fn players_file() {
+    let path = std::env::temp_dir().join(format!("networth-players-{}.txt", std::process::id()));
+    fs::write(&path, "# The guild\ncarol\n\n  alice  \nbob\n").unwrap();
+    let names = players(&["--player", "alice", "--players-file", path.to_str().unwrap(), "--player", "Dave"]).unwrap();
+    // The flags come first, then the file.
+    assert_eq!(names, ["alice", "Dave", "carol", "bob"]);
+}
+
+#[test]
+fn missing_players_file() {
+    let failure = players(&["--players-file", "/no/such/players.txt"]).unwrap_err();
+    assert!(matches!(&failure, Failure::Other(message) if message.starts_with("Unable to read the players file /no/such/players.txt")));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use std::fs;
+
+use betweenworlds_api::ItemCatalog;
+use betweenworlds_valuation::{NetworthSnapshot, Valuator};
+use chrono::{TimeZone, Utc};
+use networth::{Failure, snapshots};
+
+use common::user;
+
+fn snapshot(player: &str, second: u32) -> NetworthSnapshot {
+    let catalog = ItemCatalog::default();
+    let report = Valuator::new(&catalog).value(&user(player, Some(&[]), Some(&[])), Some(10));
+    NetworthSnapshot::new(report, &catalog, Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, second).unwrap())
+}
+
+#[test]
+fn same_player() {
+    assert!(snapshots::same_player(&snapshot("Foo", 0), &snapshot("Foo", 1)).is_ok());
+}
+
+#[test]
+fn names_that_differ_by_case_are_different_players() {
+    let failure = snapshots::same_player(&snapshot("Foo", 0), &snapshot("foo", 1)).unwrap_err();
+    assert!(matches!(&failure, Failure::Other(message) if message == "The snapshots are of different players, Foo and foo."));
+}
+
+#[test]
+fn snapshots_of_the_same_second_are_kept() {
+    let store = std::env::temp_dir().join(format!("networth-snapshots-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&store);
+    let first = snapshots::save(&store, &snapshot("Foo", 0)).unwrap();
+    let second = snapshots::save(&store, &snapshot("Foo", 0)).unwrap();
+    let third = snapshots::save(&store, &snapshot("Foo", 0)).unwrap();
+    assert_eq!(first, store.join("Foo-20230101T000000Z.json"));
+    assert_eq!(second, store.join("Foo-20230101T000000Z-2.json"));
+    assert_eq!(third, store.join("Foo-20230101T000000Z-3.json"));
+    assert_eq!(snapshots::load(&store, "Foo-20230101T000000Z-2").unwrap().taken_at, snapshot("Foo", 0).taken_at);
+    fs::remove_dir_all(&store).unwrap();
+}
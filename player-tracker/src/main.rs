@@ -1,8 +1,9 @@
 use serde::{Serialize, Deserialize};
-use std::{fs, time::Instant};
+use std::{fs, thread, time::Instant, collections::HashMap};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::time::Duration;
 use clokwerk::{Scheduler, TimeUnits};
-use betweenworlds_api::LeaderboardsFlags;
+use betweenworlds_api::{LeaderboardsFlags, CredentialsStatus, ApiError, Client, ChangeDetector, Conditional, PlayerDirectory};
 extern crate chrono;
 use chrono::{DateTime, TimeZone, LocalResult, Local};
 use num_format::{Locale, ToFormattedString};
@@ -16,8 +17,9 @@ fn main() {
     let native_options = eframe::NativeOptions::default();
 
     let mut scheduler = Scheduler::new();
-    scheduler.every(30.minutes()).run(update_records);
-    update_records();
+    let mut detectors = HashMap::new();
+    update_records(&mut detectors);
+    scheduler.every(30.minutes()).run(move || update_records(&mut detectors));
     let thread_handle = scheduler.watch_thread(Duration::from_secs(60));
 
     eframe::run_native(
@@ -33,38 +35,226 @@ struct PlayerTracker {
     current_name: String,
     selected: String,
     selected_graph: Graph,
+    credentials_check: CredentialsCheck,
+    credentials_changed: bool,
+    players: PlayerDirectory,
+    players_task: Option<Task<Result<PlayerDirectory, ApiError>>>,
+    add_task: Option<Task<Result<Option<String>, ApiError>>>,
+    add_error: Option<String>,
 }
 
 impl PlayerTracker {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let state = if let Ok(json) = fs::read_to_string(STATE_PATH) {
             serde_json::from_str(&json).unwrap()
         }
         else {
             TrackerState::default()
         };
-        Self { state, current_name: String::new(), selected: String::new(), selected_graph: Graph::Level }
+        let mut tracker = Self { 
+            state, 
+            current_name: String::new(), 
+            selected: String::new(), 
+            selected_graph: Graph::Level,
+            credentials_check: CredentialsCheck::Unchecked,
+            credentials_changed: false,
+            players: PlayerDirectory::default(),
+            players_task: None,
+            add_task: None,
+            add_error: None,
+        };
+        tracker.verify_credentials(&cc.egui_ctx);
+        tracker
     }
 
     fn update_credentials(&mut self, ui: &mut egui::Ui) {
         ui.heading("Credentials");
         let mut changed = false;
+        let mut lost_focus = false;
         ui.horizontal(|ui| {
             ui.label("Auth id").on_hover_text("Your username");
-            changed = ui.text_edit_singleline(&mut self.state.auth_id).changed();
+            let response = ui.text_edit_singleline(&mut self.state.auth_id);
+            changed = response.changed();
+            lost_focus = response.lost_focus();
         });
         ui.horizontal(|ui| {
             ui.label("Api key")
                 .on_hover_text("You can get it in the account settings.");
             let text_edit = egui::TextEdit::singleline(&mut self.state.api_key).password(true);
-            if ui.add(text_edit).changed() {
+            let response = ui.add(text_edit);
+            if response.changed() {
                 changed = true;
             }
+            if response.lost_focus() {
+                lost_focus = true;
+            }
         });
 
         if changed {
             self.save_state();
+            self.credentials_changed = true;
+        }
+        if lost_focus && self.credentials_changed {
+            self.verify_credentials(ui.ctx());
+        }
+
+        if let CredentialsCheck::Pending(task) = &self.credentials_check {
+            match task.poll() {
+                Ok(result) => {
+                    if let Ok(CredentialsStatus::Valid) = result {
+                        self.load_players(ui.ctx());
+                    }
+                    self.credentials_check = CredentialsCheck::Done(result);
+                },
+                Err(TryRecvError::Disconnected) => self.credentials_check = CredentialsCheck::Unchecked,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.label("Status");
+            match &self.credentials_check {
+                CredentialsCheck::Unchecked => {
+                    ui.weak("not checked");
+                },
+                CredentialsCheck::Pending(_) => {
+                    ui.spinner();
+                },
+                CredentialsCheck::Done(Ok(CredentialsStatus::Valid)) => {
+                    ui.colored_label(egui::Color32::GREEN, "valid");
+                },
+                CredentialsCheck::Done(Ok(CredentialsStatus::Unauthorized)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, "invalid auth id or api key");
+                },
+                CredentialsCheck::Done(Ok(CredentialsStatus::Unreachable)) => {
+                    ui.colored_label(ui.visuals().warn_fg_color, "unable to reach the api");
+                },
+                CredentialsCheck::Done(Err(error)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("unable to verify: {error}"));
+                },
+            }
+        });
+    }
+
+    /// Checks the current credentials on a background thread, the result is picked up by `update_credentials`.
+    fn verify_credentials(&mut self, ctx: &egui::Context) {
+        self.credentials_changed = false;
+        if self.state.auth_id.is_empty() || self.state.api_key.is_empty() {
+            self.credentials_check = CredentialsCheck::Unchecked;
+            return;
         }
+
+        let client = self.client();
+        self.credentials_check = CredentialsCheck::Pending(Task::spawn(ctx, move || client.verify_credentials()));
+    }
+
+    /// Loads the players on the leaderboards in the background, they're used to autocomplete the add box.
+    fn load_players(&mut self, ctx: &egui::Context) {
+        let client = self.client();
+        self.players_task = Some(Task::spawn(ctx, move || client.get_player_directory()));
+    }
+
+    fn update_add_player(&mut self, ui: &mut egui::Ui) {
+        if let Some(task) = &self.players_task {
+            match task.poll() {
+                Ok(Ok(players)) => {
+                    self.players = players;
+                    self.players_task = None;
+                },
+                Ok(Err(error)) => {
+                    eprintln!("unable to load the players: {error}");
+                    self.players_task = None;
+                },
+                Err(TryRecvError::Disconnected) => self.players_task = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        if let Some(task) = &self.add_task {
+            match task.poll() {
+                Ok(Ok(Some(name))) => {
+                    self.add_task = None;
+                    self.add_tracker(name);
+                },
+                Ok(Ok(None)) => {
+                    self.add_task = None;
+                    self.add_error = Some(format!("There is no player named {}.", self.current_name.trim()));
+                },
+                Ok(Err(error)) => {
+                    self.add_task = None;
+                    self.add_error = Some(format!("Unable to find the player: {error}."));
+                },
+                Err(TryRecvError::Disconnected) => self.add_task = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+
+        let mut add = false;
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(&mut self.current_name);
+            if response.changed() {
+                self.add_error = None;
+            }
+            add = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+            if ui.add_enabled(self.add_task.is_none(), egui::Button::new("Add")).clicked() {
+                add = true;
+            }
+        });
+
+        let name = self.current_name.trim();
+        if !name.is_empty() && self.add_task.is_none() {
+            let suggestions = self.players.search(name, 5);
+            let mut chosen = None;
+            if suggestions.first() != Some(&name) {
+                for suggestion in suggestions {
+                    let button = egui::Button::new(suggestion)
+                        .small()
+                        .frame(false)
+                        .min_size(egui::vec2(ui.available_width(), 0.0));
+                    if ui.add(button).clicked() {
+                        chosen = Some(suggestion.to_string());
+                    }
+                }
+            }
+            if let Some(chosen) = chosen {
+                self.current_name = chosen;
+            }
+        }
+
+        if self.add_task.is_some() {
+            ui.spinner();
+        }
+        if let Some(error) = &self.add_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        let name = self.current_name.trim().to_string();
+        if add && !name.is_empty() && self.add_task.is_none() {
+            if let Some(name) = self.players.lookup(&name) {
+                self.add_tracker(name.to_string());
+            }
+            else if self.state.auth_id.is_empty() || self.state.api_key.is_empty() {
+                self.add_error = Some("Enter your credentials to look up players.".to_string());
+            }
+            else {
+                let client = self.client();
+                let players = self.players.clone();
+                self.add_task = Some(Task::spawn(ui.ctx(), move || client.find_player(&name, &players)));
+            }
+        }
+    }
+
+    fn add_tracker(&mut self, name: String) {
+        if self.state.trackers.contains(&name) {
+            self.add_error = Some(format!("{name} is already tracked."));
+        }
+        else {
+            self.state.trackers.push(name);
+            self.current_name.clear();
+            self.save_state();
+        }
+    }
+
+    fn client(&self) -> Client {
+        Client::new(self.state.auth_id.clone(), self.state.api_key.clone())
     }
 
     fn update_graph(&self, ui: &mut egui::Ui, reset_graph: bool) {
@@ -134,23 +324,7 @@ impl eframe::App for PlayerTracker {
                     egui::Layout::top_down(egui::Align::Center),
                     |ui| {
 
-                        ui.horizontal(|ui| {
-                            let input_lost_focus = ui.text_edit_singleline(&mut self.current_name).lost_focus();
-                            let mut add = input_lost_focus && ui.input(|input| input.key_pressed(egui::Key::Enter));
-                            if ui.button("Add").clicked() {
-                                add = true;
-                            }
-
-                            if add && !self.current_name.is_empty() {
-                                if self.state.trackers.iter().all(|name| *name != self.current_name) {
-                                    self.state.trackers.push(self.current_name.drain(..).collect());
-                                    self.save_state();
-                                }
-                                else {
-                                    // TODO: Report an error
-                                }
-                            }
-                        }); 
+                        self.update_add_player(ui);
                         ui.add_space(5.0);
 
                         let weak_bg_fill =  ui.visuals().widgets.inactive.weak_bg_fill;
@@ -205,6 +379,33 @@ impl eframe::App for PlayerTracker {
     }
 }
 
+/// A value computed on a background thread, the ui is repainted once it's done.
+struct Task<T> {
+    receiver: Receiver<T>
+}
+
+impl<T: Send + 'static> Task<T> {
+    fn spawn<F: FnOnce() -> T + Send + 'static>(ctx: &egui::Context, job: F) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(job());
+            ctx.request_repaint();
+        });
+        Self { receiver }
+    }
+
+    fn poll(&self) -> Result<T, TryRecvError> {
+        self.receiver.try_recv()
+    }
+}
+
+enum CredentialsCheck {
+    Unchecked,
+    Pending(Task<Result<CredentialsStatus, ApiError>>),
+    Done(Result<CredentialsStatus, ApiError>),
+}
+
 #[derive(PartialEq)]
 enum Graph {
     Level,
@@ -266,7 +467,9 @@ impl LeaderboardsRecord {
     }
 }
 
-fn update_records() {
+/// Records the leaderboard values of every tracked player.
+/// `detectors` keeps one change detector per player between runs so unchanged players are skipped.
+fn update_records(detectors: &mut HashMap<String, ChangeDetector>) {
     // TODO: make this job work offline without the app open
     let state: TrackerState = if let Ok(json) = fs::read_to_string(STATE_PATH) {
         serde_json::from_str(&json).unwrap()
@@ -277,9 +480,24 @@ fn update_records() {
 
     println!("running job");
     let start = Instant::now();
-    let client = betweenworlds_api::Client::new(state.auth_id, state.api_key);
+    let client = Client::new(state.auth_id, state.api_key);
     let _ = fs::create_dir_all(TRACKERS_DIR);
     for player in state.trackers {
+        println!("player: {player}");
+        let detector = detectors.get(&player).cloned().unwrap_or_default();
+        let (user, next) = match client.get_leaderboard_user_if_changed(&player, LeaderboardsFlags::all(), &detector) {
+            Ok(Conditional::Modified(changed)) => changed,
+            Ok(Conditional::NotModified) => continue,
+            Err(ApiError::Unauthorized) => {
+                eprintln!("the api rejected the credentials, check the auth id and api key.");
+                return;
+            },
+            Err(error) => {
+                eprintln!("unable to get {player} from the leaderboards: {error}");
+                continue;
+            },
+        };
+
         let record_path = format!("{TRACKERS_DIR}/{player}.json");
         let mut record = if let Ok(json) = fs::read_to_string(&record_path) {
             serde_json::from_str(&json).unwrap()
@@ -288,13 +506,6 @@ fn update_records() {
             PlayerRecord::default()
         };
 
-        println!("player: {player}");
-        let user = match client.get_leaderboard_user(&player, LeaderboardsFlags::all()) {
-            Ok(user) => user,
-            Err(_) => continue,
-        };
-
-
         let now = Local::now();
 
         let leaderboards_record = LeaderboardsRecord {
@@ -311,8 +522,13 @@ fn update_records() {
         let previous_record = record.records.last();
         if previous_record.is_some_and(|previous_record| previous_record.has_changes(&leaderboards_record)) || previous_record.is_none() {
             record.records.push(leaderboards_record);
-            fs::write(&record_path, serde_json::to_string(&record).unwrap().as_bytes()).unwrap();
+            if let Err(error) = fs::write(&record_path, serde_json::to_string(&record).unwrap().as_bytes()) {
+                // The detector is left as is so the change is recorded on the next run.
+                eprintln!("unable to write the record of {player}: {error}");
+                continue;
+            }
         }
+        detectors.insert(player, next);
 
 
     }
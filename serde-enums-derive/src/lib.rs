@@ -0,0 +1,527 @@
+extern crate proc_macro;
+use std::collections::HashMap;
+use syn::{parse_macro_input, parse_quote, DeriveInput, Fields, Variant, Attribute, LitStr, Lit, Expr, ExprLit, Generics, GenericParam, Ident};
+use quote::{quote, format_ident};
+
+/// Derives `Deserialize` and `Serialize` for an enum whose variants are picked by a tag field.
+///
+/// The variant's payload is flattened next to the tag, so `AddCredits(MinMax)` is read from and
+/// written as `{"type": 13, "min": 1, "max": 5}`. Struct-like variants work the same way.
+/// Tuple variants with more than one field can't be flattened, their fields are kept in an array
+/// under the `data` field instead: `Range(u32, u32)` is `{"type": 3, "data": [1, 5]}`.
+///
+/// The enum also gets a few inherent items describing its variants:
+/// - `fn tag(&self) -> u64` (`&str` for string tags) returns the variant's tag.
+/// - `fn variant_name(&self) -> &'static str` returns the variant's name.
+/// - `const VARIANTS: &[(u64, &str)]` lists the tag and name of every variant but the `other` one.
+///
+/// Generic enums are supported, the generated impls require every type parameter to implement
+/// `Deserialize` (or `Serialize`) on top of the enum's own where clause.
+///
+/// The tag is read from the `type` field and has to be an unsigned integer.
+/// Variants are numbered like the enum's discriminants: an explicit `Variant = 14` sets the tag
+/// and the variants without one take the previous variant's tag plus one.
+/// `#[serde_enum(tag = 14)]` on a variant sets its tag without changing the discriminant.
+///
+/// A variant marked with `#[serde_enum(other)]` catches the tags no other variant uses instead of failing,
+/// it has to look like `Unknown { tag: u64, raw: serde_json::Value }` (`tag: String` for string tags).
+/// `raw` holds the whole object, tag included, and is written back out as is.
+/// It can be any type that deserializes from a map, e.g. `serde_json::Value` or `serde_value::Value`.
+///
+/// The generated impls only use fully qualified paths through the `serde_enums` crate and buffer the
+/// object in a format independent value, so they work with any self-describing format (JSON, MessagePack,
+/// CBOR, RON...) but not with formats like bincode that need the type to drive deserialization.
+///
+/// Container attributes:
+/// - `#[serde_enum(tag = "kind")]` reads the tag from the `kind` field.
+/// - `#[serde_enum(content = "fields")]` keeps the fields of multi-field tuple variants in the `fields` field.
+/// - `#[serde_enum(tag_type = "str")]` expects the tag to be a string,
+///   the variant's name unless it's set with `#[serde_enum(tag = "name")]`.
+#[proc_macro_derive(SerdeEnum, attributes(serde, serde_enum))]
+pub fn derive_trait(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match expand(ast) {
+        Ok(expanded) => proc_macro::TokenStream::from(expanded),
+        Err(error) => proc_macro::TokenStream::from(error.to_compile_error())
+    }
+}
+
+fn expand(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = ast.ident;
+    let container = Container::from_attributes(&ast.attrs)?;
+    let tag = &container.tag;
+    let mut current_enum_expression = 0u64;
+    let mut arms = Vec::new();
+    let mut serialize_arms = Vec::new();
+    let mut enum_values = Vec::new();
+    let mut used_tags = HashMap::new();
+    let mut other_arm = None;
+    let mut tag_arms = Vec::new();
+    let mut name_arms = Vec::new();
+    let mut variants = Vec::new();
+    let data = match ast.data {
+        syn::Data::Enum(data) => data,
+        syn::Data::Struct(data) => return Err(syn::Error::new_spanned(
+            data.struct_token,
+            "#[derive(SerdeEnum)] is only defined for enums, use serde's derives for structs"
+        )),
+        syn::Data::Union(data) => return Err(syn::Error::new_spanned(
+            data.union_token,
+            "#[derive(SerdeEnum)] is only defined for enums"
+        ))
+    };
+    let payload = PayloadGenerics::new(&name, &ast.generics);
+    for variant in data.variants {
+        let options = VariantOptions::from_attributes(&variant.attrs, &container.tag_type)?;
+        if options.other {
+            if options.tag.is_some() || variant.discriminant.is_some() {
+                return Err(syn::Error::new_spanned(&variant.ident, "the `other` variant can't have a tag"));
+            }
+            if other_arm.is_some() {
+                return Err(syn::Error::new_spanned(&variant.ident, "only one variant can be marked with `#[serde_enum(other)]`"));
+            }
+            let (deserialize, serialize) = other_variant(&name, &variant, &container)?;
+            other_arm = Some(deserialize);
+            serialize_arms.push(serialize);
+            let ident = &variant.ident;
+            let variant_name = ident.to_string();
+            tag_arms.push(match container.tag_type {
+                TagType::Uint => quote! {Self::#ident { tag, .. } => *tag},
+                TagType::Str => quote! {Self::#ident { tag, .. } => tag.as_str()}
+            });
+            name_arms.push(quote! {Self::#ident { .. } => #variant_name});
+            continue;
+        }
+        let enum_value = match (options.tag, &container.tag_type) {
+            (Some(tag), _) => tag,
+            (None, TagType::Uint) => match &variant.discriminant {
+                Some((_, discriminant)) => TagValue::Uint(discriminant_value(discriminant)?),
+                None => TagValue::Uint(current_enum_expression)
+            },
+            (None, TagType::Str) => TagValue::Str(variant.ident.to_string())
+        };
+        if let TagValue::Uint(value) = enum_value {
+            current_enum_expression = value.wrapping_add(1);
+        }
+        let enum_value_name = enum_value.to_string();
+        if let Some(previous) = used_tags.get(&enum_value_name) {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!("duplicate tag `{enum_value_name}`, it's already used by `{previous}`")
+            ));
+        }
+        used_tags.insert(enum_value_name.clone(), variant.ident.clone());
+
+        let context = format!("{name}::{} ({} {})", variant.ident, container.tag, enum_value.describe());
+        let construct = deserialize_variant(&variant, &container, &payload, &context);
+        let pattern = enum_value.pattern();
+        arms.push(quote! {#pattern => #construct});
+        serialize_arms.push(serialize_variant(&variant, &container, &enum_value, &payload));
+        enum_values.push(quote! {#enum_value_name});
+        let ident = &variant.ident;
+        let variant_name = ident.to_string();
+        tag_arms.push(quote! {Self::#ident { .. } => #pattern});
+        name_arms.push(quote! {Self::#ident { .. } => #variant_name});
+        variants.push(quote! {(#pattern, #variant_name)});
+    }
+    let read_tag = match container.tag_type {
+        TagType::Uint => quote! {
+            let enum_value = ::serde_enums::__private::tag_as_u64::<__D::Error>(&enum_value)?;
+            match enum_value
+        },
+        TagType::Str => quote! {
+            let enum_value = ::serde_enums::__private::tag_as_string::<__D::Error>(&enum_value)?;
+            match enum_value.as_str()
+        }
+    };
+    let other_arm = other_arm.unwrap_or_else(|| quote! {
+        ::core::result::Result::Err(<__D::Error as ::serde_enums::__private::serde::de::Error>::unknown_variant(
+            &::std::string::ToString::to_string(&enum_value),
+            &[#(#enum_values),*]
+        ))
+    });
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let (tag_type, tag_ref_type) = match container.tag_type {
+        TagType::Uint => (quote! {u64}, quote! {u64}),
+        TagType::Str => (quote! {&'static str}, quote! {&str})
+    };
+    let deserialize_generics = bounded_generics(&ast.generics, quote! {::serde_enums::__private::serde::Deserialize<'de>}, Some(parse_quote! {'de}));
+    let (deserialize_impl_generics, _, deserialize_where_clause) = deserialize_generics.split_for_impl();
+    let serialize_generics = bounded_generics(&ast.generics, quote! {::serde_enums::__private::serde::Serialize}, None);
+    let (serialize_impl_generics, _, serialize_where_clause) = serialize_generics.split_for_impl();
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The tags and names of the variants, without the `#[serde_enum(other)]` variant.
+            pub const VARIANTS: &'static [(#tag_type, &'static str)] = &[#(#variants),*];
+
+            /// The tag that identifies the variant.
+            pub fn tag(&self) -> #tag_ref_type {
+                match self {
+                    #(#tag_arms),*
+                }
+            }
+
+            /// The name of the variant.
+            pub fn variant_name(&self) -> &'static str {
+                match self {
+                    #(#name_arms),*
+                }
+            }
+        }
+
+        impl #deserialize_impl_generics ::serde_enums::__private::serde::Deserialize<'de> for #name #ty_generics #deserialize_where_clause {
+            fn deserialize<__D: ::serde_enums::__private::serde::Deserializer<'de>>(__deserializer: __D) -> ::core::result::Result<Self, __D::Error> {
+                let (enum_value, value) = ::serde_enums::__private::deserialize_tagged(__deserializer, #tag)?;
+                #read_tag {
+                    #(#arms,)*
+                    _ => #other_arm
+                }
+            }
+        }
+
+        impl #serialize_impl_generics ::serde_enums::__private::serde::Serialize for #name #ty_generics #serialize_where_clause {
+            fn serialize<__S: ::serde_enums::__private::serde::Serializer>(&self, __serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error> {
+                match self {
+                    #(#serialize_arms),*
+                }
+            }
+        }
+    };
+    Ok(expanded)
+}
+
+/// Options set with `#[serde_enum(...)]` on the enum.
+struct Container {
+    /// The name of the field holding the tag.
+    tag: String,
+    tag_type: TagType,
+    /// The name of the field holding the fields of multi-field tuple variants.
+    content: String
+}
+
+impl Container {
+    fn from_attributes(attributes: &[Attribute]) -> syn::Result<Self> {
+        let mut container = Container { tag: "type".to_string(), tag_type: TagType::Uint, content: "data".to_string() };
+        for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident("serde_enum")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    let tag: LitStr = meta.value()?.parse()?;
+                    container.tag = tag.value();
+                    Ok(())
+                }
+                else if meta.path.is_ident("tag_type") {
+                    let tag_type: LitStr = meta.value()?.parse()?;
+                    container.tag_type = match tag_type.value().as_str() {
+                        "uint" => TagType::Uint,
+                        "str" => TagType::Str,
+                        _ => return Err(syn::Error::new(tag_type.span(), "expected `uint` or `str`"))
+                    };
+                    Ok(())
+                }
+                else if meta.path.is_ident("content") {
+                    let content: LitStr = meta.value()?.parse()?;
+                    container.content = content.value();
+                    Ok(())
+                }
+                else {
+                    Err(meta.error("unknown serde_enum attribute, expected `tag`, `tag_type` or `content`"))
+                }
+            })?;
+        }
+        Ok(container)
+    }
+}
+
+/// Options set with `#[serde_enum(...)]` on a variant.
+struct VariantOptions {
+    tag: Option<TagValue>,
+    /// The variant catches all the unknown tags.
+    other: bool
+}
+
+impl VariantOptions {
+    fn from_attributes(attributes: &[Attribute], tag_type: &TagType) -> syn::Result<Self> {
+        let mut options = VariantOptions { tag: None, other: false };
+        for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident("serde_enum")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    let tag: Lit = meta.value()?.parse()?;
+                    options.tag = Some(match (tag_type, tag) {
+                        (TagType::Uint, Lit::Int(tag)) => TagValue::Uint(tag.base10_parse()?),
+                        (TagType::Str, Lit::Str(tag)) => TagValue::Str(tag.value()),
+                        (TagType::Uint, tag) => return Err(syn::Error::new_spanned(tag, "expected an unsigned integer tag")),
+                        (TagType::Str, tag) => return Err(syn::Error::new_spanned(tag, "expected a string tag"))
+                    });
+                    Ok(())
+                }
+                else if meta.path.is_ident("other") {
+                    options.other = true;
+                    Ok(())
+                }
+                else {
+                    Err(meta.error("unknown serde_enum variant attribute, expected `tag` or `other`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// Reads an explicit discriminant (`Variant = 14`) as a tag.
+fn discriminant_value(discriminant: &Expr) -> syn::Result<u64> {
+    match discriminant {
+        Expr::Lit(ExprLit { lit: Lit::Int(value), .. }) => value.base10_parse(),
+        _ => Err(syn::Error::new_spanned(
+            discriminant,
+            "only integer literal discriminants can be used as tags, use `#[serde_enum(tag = ...)]` instead"
+        ))
+    }
+}
+
+enum TagType {
+    Uint,
+    Str
+}
+
+/// The tag that selects a variant.
+enum TagValue {
+    Uint(u64),
+    Str(String)
+}
+
+impl TagValue {
+    fn pattern(&self) -> proc_macro2::TokenStream {
+        match self {
+            TagValue::Uint(value) => quote! {#value},
+            TagValue::Str(value) => quote! {#value}
+        }
+    }
+
+    /// The tag as it's shown in error messages, strings are quoted.
+    fn describe(&self) -> String {
+        match self {
+            TagValue::Uint(value) => value.to_string(),
+            TagValue::Str(value) => format!("{value:?}")
+        }
+    }
+}
+
+impl std::fmt::Display for TagValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagValue::Uint(value) => write!(f, "{value}"),
+            TagValue::Str(value) => write!(f, "{value}")
+        }
+    }
+}
+
+/// Generates an expression that deserializes the variant's payload from `value` and constructs the variant.
+/// Errors are prefixed with `context` to tell which variant failed.
+fn deserialize_variant(variant: &Variant, container: &Container, payload: &PayloadGenerics, context: &str) -> proc_macro2::TokenStream {
+    let ident = &variant.ident;
+    let PayloadGenerics { params, where_clause, arguments, phantom } = payload;
+    match &variant.fields {
+        Fields::Named(fields) => {
+            // The fields are deserialized through a struct with the same fields so `#[serde]` field attributes keep working,
+            // its name is reserved so it can't shadow a type of the fields.
+            let field_names: Vec<_> = fields.named.iter().map(|field| &field.ident).collect();
+            let field_types = fields.named.iter().map(|field| &field.ty);
+            let field_attributes: Vec<Vec<_>> = fields.named.iter().map(|field| serde_attributes(&field.attrs).collect()).collect();
+            quote! {{
+                #[derive(::serde_enums::__private::serde::Deserialize)]
+                #[serde(crate = "::serde_enums::__private::serde")]
+                struct __SerdeEnumPayload<#params> #where_clause {
+                    #(#(#field_attributes)* #field_names: #field_types,)*
+                    #[serde(skip)]
+                    __phantom: #phantom
+                }
+                let payload = ::serde_enums::__private::from_value::<__SerdeEnumPayload<#(#arguments),*>, __D::Error>(value, #context)?;
+                ::core::result::Result::Ok(Self::#ident { #(#field_names: payload.#field_names),* })
+            }}
+        },
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let typ = &fields.unnamed[0].ty;
+            quote! {
+                ::core::result::Result::Ok(Self::#ident(::serde_enums::__private::from_value::<#typ, __D::Error>(value, #context)?))
+            }
+        },
+        Fields::Unnamed(fields) if fields.unnamed.is_empty() => quote! {::core::result::Result::Ok(Self::#ident())},
+        Fields::Unnamed(fields) => {
+            let content = &container.content;
+            let field_types = fields.unnamed.iter().map(|field| &field.ty);
+            let bindings: Vec<_> = (0..fields.unnamed.len()).map(|index| format_ident!("__field{}", index)).collect();
+            quote! {{
+                let content = ::serde_enums::__private::take_field::<__D::Error>(value, #content, #context)?;
+                let (#(#bindings,)*) = ::serde_enums::__private::from_value::<(#(#field_types,)*), __D::Error>(content, #context)?;
+                ::core::result::Result::Ok(Self::#ident(#(#bindings),*))
+            }}
+        },
+        Fields::Unit => quote! {::core::result::Result::Ok(Self::#ident)}
+    }
+}
+
+/// Generates the deserialization expression and the serialization match arm of the `#[serde_enum(other)]` variant.
+fn other_variant(name: &Ident, variant: &Variant, container: &Container) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let ident = &variant.ident;
+    let error = || {
+        let message = "the `other` variant must have exactly two named fields, `tag` and `raw`";
+        match &variant.fields {
+            Fields::Unit => syn::Error::new_spanned(ident, message),
+            fields => syn::Error::new_spanned(fields, message)
+        }
+    };
+    let Fields::Named(fields) = &variant.fields else {
+        return Err(error());
+    };
+    let has_field = |name: &str| fields.named.iter().any(|field| field.ident.as_ref().is_some_and(|ident| ident == name));
+    if fields.named.len() != 2 || !has_field("tag") || !has_field("raw") {
+        return Err(error());
+    }
+    let raw = fields.named.iter()
+        .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "raw"))
+        .map(|field| &field.ty);
+
+    let context = match container.tag_type {
+        TagType::Uint => format!("{name}::{ident} ({} {{}})", container.tag),
+        TagType::Str => format!("{name}::{ident} ({} {{:?}})", container.tag)
+    };
+    let deserialize = quote! {{
+        let context = ::std::format!(#context, enum_value);
+        ::core::result::Result::Ok(Self::#ident {
+            raw: ::serde_enums::__private::from_value::<#raw, __D::Error>(value, &context)?,
+            tag: ::core::convert::From::from(enum_value)
+        })
+    }};
+    let serialize = quote! {
+        Self::#ident { raw, .. } => ::serde_enums::__private::serde::Serialize::serialize(raw, __serializer)
+    };
+    Ok((deserialize, serialize))
+}
+
+/// Generates a match arm that serializes the variant as its tag followed by its flattened payload.
+fn serialize_variant(variant: &Variant, container: &Container, enum_value: &TagValue, payload: &PayloadGenerics) -> proc_macro2::TokenStream {
+    let ident = &variant.ident;
+    let tag = &container.tag;
+    let tag_value = enum_value.pattern();
+    let tag_type = match container.tag_type {
+        TagType::Uint => quote! {u64},
+        TagType::Str => quote! {&'static str}
+    };
+    let PayloadGenerics { params, where_clause, phantom, .. } = payload;
+    // The payload borrows the fields for `'__a`, a lifetime that can't clash with the enum's own.
+    let (pattern, payload_fields, payload_values) = match &variant.fields {
+        Fields::Named(fields) if !fields.named.is_empty() => {
+            let field_names: Vec<_> = fields.named.iter().map(|field| &field.ident).collect();
+            let field_types = fields.named.iter().map(|field| &field.ty);
+            let field_attributes: Vec<Vec<_>> = fields.named.iter().map(|field| serde_attributes(&field.attrs).collect()).collect();
+            (
+                quote! {Self::#ident { #(#field_names),* }},
+                quote! {#(#(#field_attributes)* #field_names: &'__a #field_types,)*},
+                quote! {#(#field_names,)*}
+            )
+        },
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let typ = &fields.unnamed[0].ty;
+            (
+                quote! {Self::#ident(__field0)},
+                quote! {#[serde(flatten)] __field0: &'__a #typ,},
+                quote! {__field0,}
+            )
+        },
+        Fields::Unnamed(fields) if !fields.unnamed.is_empty() => {
+            let content = &container.content;
+            let bindings: Vec<_> = (0..fields.unnamed.len()).map(|index| format_ident!("__field{}", index)).collect();
+            let field_types = fields.unnamed.iter().map(|field| &field.ty);
+            (
+                quote! {Self::#ident(#(#bindings),*)},
+                quote! {#[serde(rename = #content)] __content: (#(&'__a #field_types,)*),},
+                quote! {__content: (#(#bindings,)*),}
+            )
+        },
+        _ => {
+            let pattern = match &variant.fields {
+                Fields::Named(_) => quote! {Self::#ident {}},
+                Fields::Unnamed(_) => quote! {Self::#ident()},
+                Fields::Unit => quote! {Self::#ident}
+            };
+            return quote! {
+                #pattern => {
+                    let mut map = ::serde_enums::__private::serde::Serializer::serialize_map(__serializer, ::core::option::Option::Some(1))?;
+                    ::serde_enums::__private::serde::ser::SerializeMap::serialize_entry(&mut map, #tag, &#tag_value)?;
+                    ::serde_enums::__private::serde::ser::SerializeMap::end(map)
+                }
+            };
+        }
+    };
+    // The helper's name is reserved so it can't shadow a type of the fields.
+    quote! {
+        #pattern => {
+            #[derive(::serde_enums::__private::serde::Serialize)]
+            #[serde(crate = "::serde_enums::__private::serde")]
+            struct __SerdeEnumPayload<'__a, #params> #where_clause {
+                #[serde(rename = #tag)]
+                __tag: #tag_type,
+                #payload_fields
+                #[serde(skip)]
+                __phantom: #phantom
+            }
+            let payload = __SerdeEnumPayload {
+                __tag: #tag_value,
+                #payload_values
+                __phantom: ::core::marker::PhantomData::<fn() -> Self>
+            };
+            ::serde_enums::__private::serde::Serialize::serialize(&payload, __serializer)
+        }
+    }
+}
+
+/// The enum's generics as the helper `__SerdeEnumPayload` structs need them,
+/// items nested in the generated impls can't use the impls' generic parameters.
+struct PayloadGenerics {
+    /// The enum's generic parameters with their bounds, `'a, T: Clone`.
+    params: syn::punctuated::Punctuated<GenericParam, syn::token::Comma>,
+    where_clause: Option<syn::WhereClause>,
+    /// The parameters' names, `'a, T`.
+    arguments: Vec<proc_macro2::TokenStream>,
+    /// The type of the skipped field that uses every parameter.
+    phantom: proc_macro2::TokenStream
+}
+
+impl PayloadGenerics {
+    fn new(name: &Ident, generics: &Generics) -> Self {
+        let arguments: Vec<_> = generics.params.iter().map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote! {#lifetime}
+            },
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! {#ident}
+            },
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! {#ident}
+            }
+        }).collect();
+        let phantom = quote! {::core::marker::PhantomData<fn() -> #name<#(#arguments),*>>};
+        Self { params: generics.params.clone(), where_clause: generics.where_clause.clone(), arguments, phantom }
+    }
+}
+
+/// Adds a bound to every type parameter of the enum and an extra lifetime before the others.
+fn bounded_generics(generics: &Generics, bound: proc_macro2::TokenStream, lifetime: Option<syn::LifetimeParam>) -> Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<_> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in type_params {
+        where_clause.predicates.push(parse_quote! {#param: #bound});
+    }
+    if let Some(lifetime) = lifetime {
+        generics.params.insert(0, GenericParam::Lifetime(lifetime));
+    }
+    generics
+}
+
+fn serde_attributes(attributes: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attributes.iter().filter(|attribute| attribute.path().is_ident("serde"))
+}
@@ -1,68 +1,90 @@
-extern crate proc_macro;
-use syn::{parse_macro_input, DeriveInput, Fields};
-use quote::quote;
+//! Serde support for enums whose variant is picked by a tag field of the object, see [`SerdeEnum`].
 
-#[proc_macro_derive(SerdeEnum)]
-pub fn derive_trait(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast = parse_macro_input!(input as DeriveInput);
+pub use serde_enums_derive::SerdeEnum;
 
-    let name = ast.ident;
-    let tag = "type";
-    let mut current_enum_expression = 0u64;
-    let mut arms = Vec::new();
-    let mut enum_values = Vec::new();
-    if let syn::Data::Enum(data) = ast.data {
-        for variant in data.variants {
-            let ident = variant.ident;
-            let fields = desserialize_enum_fields(variant.fields);
-            let streams: &[proc_macro2::TokenStream] = &[quote! { #ident }, fields];
-            let arm = quote! {#current_enum_expression => Ok(<#name>::#(#streams)*)};
-            arms.push(arm);
-            let enum_value = current_enum_expression.to_string();
-            enum_values.push(quote! {#enum_value});
-            current_enum_expression += 1;
-        }
-        let expanded = quote! {
-            impl<'de> Deserialize<'de> for #name {
-                fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
-                    let value = Value::deserialize(d)?;
-                    let enum_value = value.get(#tag).ok_or(serde::de::Error::missing_field(#tag))?;
-                    let enum_value = Value::as_u64(enum_value).ok_or(serde::de::Error::custom(format!("Unexpected `{}`, expected uint", enum_value.to_string())))?;
-                    match enum_value {
-                        #(#arms),*,
-                        _ => Err(serde::de::Error::unknown_variant(&enum_value.to_string(), &[#(#enum_values),*]))
-                    }
-                }
-            }
+/// Used by the code `#[derive(SerdeEnum)]` generates, not public api.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+    pub use serde_value::Value;
+
+    use serde::de::{Deserialize, Deserializer, Error, Unexpected};
+    use serde_value::ValueDeserializer;
 
-            
+    /// Buffers the object being deserialized and reads its tag.
+    /// Returns the tag and the whole object, tag included.
+    pub fn deserialize_tagged<'de, D: Deserializer<'de>>(deserializer: D, tag: &'static str) -> Result<(Value, Value), D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let Value::Map(map) = &value else {
+            return Err(D::Error::invalid_type(unexpected(&value), &"a map"));
         };
-        proc_macro::TokenStream::from(expanded)
+        let tag_value = map.get(&Value::String(tag.to_string()))
+            .cloned()
+            .ok_or_else(|| D::Error::missing_field(tag))?;
+        Ok((tag_value, value))
     }
-    else {
-        panic!("#[derive(SerdeEnum)] is only defined for enums!");
+
+    pub fn tag_as_u64<E: Error>(tag: &Value) -> Result<u64, E> {
+        let value = match *tag {
+            Value::U8(value) => Some(value as u64),
+            Value::U16(value) => Some(value as u64),
+            Value::U32(value) => Some(value as u64),
+            Value::U64(value) => Some(value),
+            Value::I8(value) => u64::try_from(value).ok(),
+            Value::I16(value) => u64::try_from(value).ok(),
+            Value::I32(value) => u64::try_from(value).ok(),
+            Value::I64(value) => u64::try_from(value).ok(),
+            _ => None
+        };
+        value.ok_or_else(|| E::invalid_type(unexpected(tag), &"an unsigned integer tag"))
     }
-    
 
-}
+    pub fn tag_as_string<E: Error>(tag: &Value) -> Result<String, E> {
+        match tag {
+            Value::String(value) => Ok(value.clone()),
+            Value::Char(value) => Ok(value.to_string()),
+            _ => Err(E::invalid_type(unexpected(tag), &"a string tag"))
+        }
+    }
 
-fn desserialize_enum_fields(fields: Fields) -> proc_macro2::TokenStream {
-    match fields {
-        Fields::Named(_fields) => unimplemented!(),
-        Fields::Unnamed(fields) => {
-            let mut values = Vec::new();
-            for field in fields.unnamed {
-                let typ = field.ty;
-                let field = quote!{
-                    match <#typ>::deserialize(value) {
-                        Ok(value) => value,
-                        Err(error) => Err(serde::de::Error::custom(format!("{}", error)))?,
-                    }};
-                values.push(field);
-            }
-            quote! {(#(#values),*)}
-        },
-        Fields::Unit => quote! {}
+    /// Takes a field out of a buffered map.
+    /// `context` describes what's being deserialized and is added to the errors, e.g. `Effect::AddItem (type 15)`.
+    pub fn take_field<E: Error>(value: Value, key: &'static str, context: &str) -> Result<Value, E> {
+        let Value::Map(mut map) = value else {
+            return Err(E::custom(format_args!("{context}: invalid type: {}, expected a map", unexpected(&value))));
+        };
+        map.remove(&Value::String(key.to_string()))
+            .ok_or_else(|| E::custom(format_args!("{context}: missing field `{key}`")))
+    }
+
+    /// Deserializes a buffered value keeping the original deserializer's error type.
+    /// `context` describes what's being deserialized and is added to the errors, e.g. `Effect::AddItem (type 15)`.
+    pub fn from_value<'de, T: Deserialize<'de>, E: Error>(value: Value, context: &str) -> Result<T, E> {
+        T::deserialize(ValueDeserializer::<E>::new(value))
+            .map_err(|error| E::custom(format_args!("{context}: {error}")))
     }
-}
 
+    fn unexpected(value: &Value) -> Unexpected<'_> {
+        match value {
+            Value::Bool(value) => Unexpected::Bool(*value),
+            Value::U8(value) => Unexpected::Unsigned(*value as u64),
+            Value::U16(value) => Unexpected::Unsigned(*value as u64),
+            Value::U32(value) => Unexpected::Unsigned(*value as u64),
+            Value::U64(value) => Unexpected::Unsigned(*value),
+            Value::I8(value) => Unexpected::Signed(*value as i64),
+            Value::I16(value) => Unexpected::Signed(*value as i64),
+            Value::I32(value) => Unexpected::Signed(*value as i64),
+            Value::I64(value) => Unexpected::Signed(*value),
+            Value::F32(value) => Unexpected::Float(*value as f64),
+            Value::F64(value) => Unexpected::Float(*value),
+            Value::Char(value) => Unexpected::Char(*value),
+            Value::String(value) => Unexpected::Str(value),
+            Value::Unit => Unexpected::Unit,
+            Value::Option(_) => Unexpected::Option,
+            Value::Newtype(_) => Unexpected::NewtypeStruct,
+            Value::Seq(_) => Unexpected::Seq,
+            Value::Map(_) => Unexpected::Map,
+            Value::Bytes(value) => Unexpected::Bytes(value)
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_enums::SerdeEnum;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AddItem {
+    #[serde(rename = "itemName")]
+    _item_name: String
+}
+
+#[derive(Debug, SerdeEnum)]
+#[repr(u8)]
+enum ConsumeEffect {
+    Unknown,
+    AddItem(AddItem) = 15,
+    Range(u32, u32),
+    #[serde_enum(other)]
+    #[allow(dead_code)]
+    Unrecognized { tag: u64, raw: Vec<u8> }
+}
+
+#[derive(Debug, SerdeEnum)]
+#[serde_enum(tag = "kind", tag_type = "str")]
+enum Module {
+    Boost { amount: u32 }
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    #[serde(rename = "consumeEffects")]
+    _consume_effects: Vec<ConsumeEffect>
+}
+
+fn error<T: for<'de> Deserialize<'de> + std::fmt::Debug>(json: serde_json::Value) -> String {
+    serde_json::from_value::<T>(json).unwrap_err().to_string()
+}
+
+#[test]
+fn names_the_variant_and_tag() {
+    assert_eq!(
+        error::<ConsumeEffect>(json!({"type": 15, "chance": 1})),
+        "ConsumeEffect::AddItem (type 15): missing field `itemName`"
+    );
+    assert_eq!(
+        error::<ConsumeEffect>(json!({"type": 16})),
+        "ConsumeEffect::Range (type 16): missing field `data`"
+    );
+    assert_eq!(
+        error::<ConsumeEffect>(json!({"type": 16, "data": [1, "2"]})),
+        "ConsumeEffect::Range (type 16): invalid type: string \"2\", expected u32"
+    );
+    assert_eq!(
+        error::<Module>(json!({"kind": "Boost", "amount": -1})),
+        "Module::Boost (kind \"Boost\"): invalid value: integer `-1`, expected u32"
+    );
+}
+
+#[test]
+fn names_the_other_variant_and_tag() {
+    assert_eq!(
+        error::<ConsumeEffect>(json!({"type": 40})),
+        "ConsumeEffect::Unrecognized (type 40): invalid type: map, expected a sequence"
+    );
+}
+
+#[test]
+fn path_to_error() {
+    let json = json!([
+        {"consumeEffects": [{"type": 0}]},
+        {"consumeEffects": [{"type": 0}, {"type": 15, "chance": 1}]}
+    ]).to_string();
+    let deserializer = &mut serde_json::Deserializer::from_str(&json);
+    let error = serde_path_to_error::deserialize::<_, Vec<Item>>(deserializer).unwrap_err();
+    assert_eq!(error.path().to_string(), "[1].consumeEffects[1]");
+    assert!(error.inner().to_string().starts_with("ConsumeEffect::AddItem (type 15): missing field `itemName`"));
+}
@@ -0,0 +1,59 @@
+// Nothing from serde is imported here, the generated code mustn't depend on the caller's imports.
+mod effects {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    pub struct MinMax {
+        pub min: isize,
+        pub max: isize
+    }
+
+    #[derive(Debug, PartialEq, serde_enums::SerdeEnum)]
+    #[repr(u8)]
+    pub enum Effect {
+        Unknown,
+        RestoreHealth(MinMax),
+        AddCredits {
+            min: isize,
+            max: isize
+        } = 13,
+        #[serde_enum(other)]
+        Unrecognized {
+            tag: u64,
+            raw: serde_value::Value
+        }
+    }
+}
+
+use effects::{Effect, MinMax};
+
+fn effects() -> Vec<Effect> {
+    vec![
+        Effect::Unknown,
+        Effect::RestoreHealth(MinMax { min: 1, max: 5 }),
+        Effect::AddCredits { min: -3, max: 200 }
+    ]
+}
+
+#[test]
+fn message_pack() {
+    let bytes = rmp_serde::to_vec_named(&effects()).unwrap();
+    let effects_back: Vec<Effect> = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(effects_back, effects());
+}
+
+#[test]
+fn message_pack_other_variant() {
+    let bytes = rmp_serde::to_vec_named(&serde_json::json!({"type": 99, "amount": 4})).unwrap();
+    let effect: Effect = rmp_serde::from_slice(&bytes).unwrap();
+    let Effect::Unrecognized { tag, raw } = &effect else {
+        panic!("expected the other variant, got {effect:?}");
+    };
+    assert_eq!(*tag, 99);
+    assert_eq!(rmp_serde::to_vec_named(raw).unwrap(), bytes);
+}
+
+#[test]
+fn json() {
+    let json = serde_json::to_value(effects()).unwrap();
+    let effects_back: Vec<Effect> = serde_json::from_value(json).unwrap();
+    assert_eq!(effects_back, effects());
+}
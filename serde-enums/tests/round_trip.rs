@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_enums::SerdeEnum;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct MinMax {
+    min: isize,
+    max: isize
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct AddItem {
+    chance: f32,
+    #[serde(rename = "itemName")]
+    item_name: String
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+#[repr(u8)]
+enum Effect {
+    Unknown = 0,
+    RestoreHealth(MinMax) = 1,
+    AddItem(AddItem) = 15,
+    AddCredits {
+        min: isize,
+        max: isize,
+        #[serde(rename = "worthMultiplier")]
+        worth_multiplier: usize
+    } = 13
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+#[serde_enum(tag = "kind", tag_type = "str")]
+enum Module {
+    Empty,
+    #[serde_enum(tag = "boost")]
+    Boost { amount: u32 }
+}
+
+/// Named like the helper structs the derive used to generate, the variants must still see this type.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Payload {
+    weight: u32
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+#[repr(u8)]
+enum Parcel {
+    Boxed { payload: Payload } = 1,
+    Loose(Payload) = 2
+}
+
+fn round_trip<T: for<'de> Deserialize<'de> + Serialize>(json: Value) -> T {
+    let value: T = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&value).unwrap(), json);
+    value
+}
+
+#[test]
+fn unit_variant() {
+    assert_eq!(round_trip::<Effect>(json!({"type": 0})), Effect::Unknown);
+}
+
+#[test]
+fn newtype_variant() {
+    assert_eq!(
+        round_trip::<Effect>(json!({"type": 1, "min": 5, "max": 10})),
+        Effect::RestoreHealth(MinMax { min: 5, max: 10 })
+    );
+    assert_eq!(
+        round_trip::<Effect>(json!({"type": 15, "chance": 0.5, "itemName": "Bandage"})),
+        Effect::AddItem(AddItem { chance: 0.5, item_name: "Bandage".to_string() })
+    );
+}
+
+#[test]
+fn struct_variant() {
+    assert_eq!(
+        round_trip::<Effect>(json!({"type": 13, "min": 100, "max": 200, "worthMultiplier": 3})),
+        Effect::AddCredits { min: 100, max: 200, worth_multiplier: 3 }
+    );
+}
+
+#[test]
+fn string_tags() {
+    assert_eq!(round_trip::<Module>(json!({"kind": "Empty"})), Module::Empty);
+    assert_eq!(round_trip::<Module>(json!({"kind": "boost", "amount": 2})), Module::Boost { amount: 2 });
+}
+
+#[test]
+fn sequence() {
+    let json = json!([
+        {"type": 1, "min": 1, "max": 2},
+        {"type": 0},
+        {"type": 13, "min": 3, "max": 4, "worthMultiplier": 1}
+    ]);
+    let effects: Vec<Effect> = round_trip(json);
+    assert_eq!(effects.len(), 3);
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+enum OpenEffect {
+    RestoreHealth(MinMax),
+    #[serde_enum(other)]
+    Unrecognized { tag: u64, raw: Value }
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+#[serde_enum(tag_type = "str")]
+enum OpenModule {
+    Empty,
+    #[serde_enum(other)]
+    Unrecognized { tag: String, raw: Value }
+}
+
+#[test]
+fn other_variant() {
+    assert_eq!(
+        round_trip::<OpenEffect>(json!({"type": 0, "min": 1, "max": 2})),
+        OpenEffect::RestoreHealth(MinMax { min: 1, max: 2 })
+    );
+    let json = json!({"type": 42, "something": [1, 2, 3]});
+    assert_eq!(round_trip::<OpenEffect>(json.clone()), OpenEffect::Unrecognized { tag: 42, raw: json });
+
+    let json = json!({"type": "Shiny", "power": 3});
+    assert_eq!(round_trip::<OpenModule>(json.clone()), OpenModule::Unrecognized { tag: "Shiny".to_string(), raw: json });
+    assert_eq!(round_trip::<OpenModule>(json!({"type": "Empty"})), OpenModule::Empty);
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+enum Range {
+    Empty,
+    Between(u32, u32),
+    #[serde_enum(tag = 5)]
+    Labelled(String, u32, u32)
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+#[serde_enum(content = "fields")]
+enum Point {
+    Flat(i32, i32)
+}
+
+#[test]
+fn multi_field_tuple_variant() {
+    assert_eq!(round_trip::<Range>(json!({"type": 1, "data": [1, 5]})), Range::Between(1, 5));
+    assert_eq!(
+        round_trip::<Range>(json!({"type": 5, "data": ["level", 2, 9]})),
+        Range::Labelled("level".to_string(), 2, 9)
+    );
+    assert_eq!(round_trip::<Point>(json!({"type": 0, "fields": [-1, 3]})), Point::Flat(-1, 3));
+    assert!(serde_json::from_value::<Range>(json!({"type": 1, "min": 1, "max": 5})).is_err());
+}
+
+#[derive(Debug, PartialEq, SerdeEnum)]
+enum Reward<T, const N: usize>
+where
+    T: Clone
+{
+    Nothing,
+    Single(T),
+    Many { items: Vec<T>, limit: u8 },
+    Pair(T, T)
+}
+
+#[test]
+fn generic_enum() {
+    assert_eq!(round_trip::<Reward<MinMax, 2>>(json!({"type": 0})), Reward::Nothing);
+    assert_eq!(
+        round_trip::<Reward<MinMax, 2>>(json!({"type": 1, "min": 1, "max": 2})),
+        Reward::Single(MinMax { min: 1, max: 2 })
+    );
+    assert_eq!(
+        round_trip::<Reward<String, 2>>(json!({"type": 2, "items": ["a", "b"], "limit": 3})),
+        Reward::Many { items: vec!["a".to_string(), "b".to_string()], limit: 3 }
+    );
+    assert_eq!(
+        round_trip::<Reward<String, 0>>(json!({"type": 3, "data": ["a", "b"]})),
+        Reward::Pair("a".to_string(), "b".to_string())
+    );
+}
+
+#[test]
+fn fields_of_a_type_named_payload() {
+    assert_eq!(
+        round_trip::<Parcel>(json!({"type": 1, "payload": {"weight": 3}})),
+        Parcel::Boxed { payload: Payload { weight: 3 } }
+    );
+    assert_eq!(round_trip::<Parcel>(json!({"type": 2, "weight": 4})), Parcel::Loose(Payload { weight: 4 }));
+}
+
+#[test]
+fn variant_metadata() {
+    assert_eq!(Effect::VARIANTS, &[(0, "Unknown"), (1, "RestoreHealth"), (15, "AddItem"), (13, "AddCredits")]);
+    let effect = Effect::AddCredits { min: 1, max: 2, worth_multiplier: 3 };
+    assert_eq!((effect.tag(), effect.variant_name()), (13, "AddCredits"));
+
+    assert_eq!(Module::VARIANTS, &[("Empty", "Empty"), ("boost", "Boost")]);
+    assert_eq!(Module::Boost { amount: 1 }.tag(), "boost");
+
+    assert_eq!(OpenEffect::VARIANTS, &[(0, "RestoreHealth")]);
+    let effect = OpenEffect::Unrecognized { tag: 42, raw: json!({"type": 42}) };
+    assert_eq!((effect.tag(), effect.variant_name()), (42, "Unrecognized"));
+    let module = OpenModule::Unrecognized { tag: "Shiny".to_string(), raw: json!({"type": "Shiny"}) };
+    assert_eq!((module.tag(), module.variant_name()), ("Shiny", "Unrecognized"));
+
+    assert_eq!(Reward::<String, 1>::Pair("a".to_string(), "b".to_string()).variant_name(), "Pair");
+}
@@ -0,0 +1,9 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+#[serde_enum(tag_type = "int")]
+enum Effect {
+    Unknown
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+enum Effect {
+    Unknown,
+    #[serde_enum(tag = 0)]
+    Heal
+}
+
+fn main() {}
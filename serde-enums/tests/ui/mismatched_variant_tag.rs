@@ -0,0 +1,10 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+enum Effect {
+    Unknown,
+    #[serde_enum(tag = "heal")]
+    Heal
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use serde_enums::SerdeEnum;
+
+const HEAL: isize = 3;
+
+#[derive(SerdeEnum)]
+enum Effect {
+    Unknown,
+    Heal = HEAL
+}
+
+fn main() {}
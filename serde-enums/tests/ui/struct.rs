@@ -0,0 +1,9 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+struct Effect {
+    min: isize,
+    max: isize
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+enum Effect {
+    Unknown,
+    #[serde_enum(other)]
+    Unrecognized { tag: u64, raw: serde_json::Value },
+    #[serde_enum(other)]
+    Unsupported { tag: u64, raw: serde_json::Value }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+union Effect {
+    min: isize,
+    max: usize
+}
+
+fn main() {}
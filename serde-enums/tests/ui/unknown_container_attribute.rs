@@ -0,0 +1,9 @@
+use serde_enums::SerdeEnum;
+
+#[derive(SerdeEnum)]
+#[serde_enum(rename_all = "camelCase")]
+enum Effect {
+    Unknown
+}
+
+fn main() {}